@@ -0,0 +1,10 @@
+//! Checks that the hrdb parser never panics, whatever UTF-8 it is fed.
+//!
+//! Run with `cargo fuzz run hrdb` from the repository root.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+	let _ = sprechzeiten::hrdb::offices(input);
+});
@@ -0,0 +1,389 @@
+//! A log of the calls made to offices, to keep track of the search.
+//!
+//! The log is a text file to which each call is appended as a line, so that
+//! nothing is ever lost by rewriting it. A line consists of the time, the id
+//! of the office (see `Office::id`), the number dialed (possibly empty) and
+//! the outcome, separated by tabs, e.g.
+//! `"2026-10-19 14:03\tingrid-allissat-iris-prawitz\t+49615161544\tbesetzt"`.
+//! The offices in `data/therapeuten.hrdb` are given such ids explicitly by
+//! `Id:` lines, so that they stay the same when their names change.
+
+use std::fmt;
+use std::str::FromStr;
+
+use ::time::{Date, Month, OffsetDateTime, PrimitiveDateTime};
+
+use crate::phone::Phone;
+use crate::search;
+use crate::Offices;
+
+/// The id of an office written as in `"Ingrid Allißat"`, i.e. the folded
+/// words (see `search::fold`) joined by dashes, e.g. `"ingrid-allissat"`.
+pub fn slug(src: &str) -> String {
+	let folded = search::fold(src);
+	let words = folded.split(|c: char| !c.is_ascii_alphanumeric());
+	let words: Vec<&str> = words.filter(|x| !x.is_empty()).collect();
+	words.join("-")
+}
+
+/// What came of a call.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Outcome {
+	/// Someone answered.
+	Erreicht,
+	Besetzt,
+	/// The answering machine answered.
+	AB,
+	KeineAntwort,
+	/// Someone answered and promised to call back.
+	RueckrufZugesagt,
+	/// Someone answered and put us on the waiting list.
+	Warteliste,
+	/// Someone answered and turned us down.
+	Absage,
+}
+
+const OUTCOMES: [Outcome; 7] = [
+	Outcome::Erreicht,
+	Outcome::Besetzt,
+	Outcome::AB,
+	Outcome::KeineAntwort,
+	Outcome::RueckrufZugesagt,
+	Outcome::Warteliste,
+	Outcome::Absage,
+];
+
+#[derive(Debug)]
+pub enum OutcomeErr {
+	Unknown,
+}
+
+impl FromStr for Outcome {
+	type Err = OutcomeErr;
+
+	/// Parses the outcomes ignoring case and umlauts, with words separated
+	/// by spaces or dashes, e.g. `"keine-antwort"`. Of the outcomes of two
+	/// words, the first word suffices, e.g. `"Rückruf"`.
+	fn from_str(src: &str) -> Result<Outcome, Self::Err> {
+		let src = slug(src);
+		let matches = |x: &Outcome| {
+			let outcome = slug(&x.to_string());
+			let first_word = outcome.split('-').next();
+			outcome == src || first_word == Some(&src)
+		};
+		OUTCOMES
+			.into_iter()
+			.find(matches)
+			.ok_or(OutcomeErr::Unknown)
+	}
+}
+
+impl fmt::Display for Outcome {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Outcome::Erreicht => write!(fmt, "erreicht"),
+			Outcome::Besetzt => write!(fmt, "besetzt"),
+			Outcome::AB => write!(fmt, "AB"),
+			Outcome::KeineAntwort => write!(fmt, "keine Antwort"),
+			Outcome::RueckrufZugesagt => {
+				write!(fmt, "Rückruf zugesagt")
+			}
+			Outcome::Warteliste => write!(fmt, "Warteliste"),
+			Outcome::Absage => write!(fmt, "Absage"),
+		}
+	}
+}
+
+/// A call to an office.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Call {
+	/// The local time of the call, to the minute.
+	time: PrimitiveDateTime,
+	/// The id of the office called.
+	office: String,
+	number: Option<Phone>,
+	outcome: Outcome,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CallErr {
+	MissingField,
+	InvalidTime,
+	InvalidNumber,
+	InvalidOutcome,
+}
+
+impl Call {
+	fn new(
+		time: PrimitiveDateTime,
+		office: &str,
+		number: Option<Phone>,
+		outcome: Outcome,
+	) -> Call {
+		let office = String::from(office);
+		Call { time, office, number, outcome }
+	}
+
+	/// A call to the office with the given id just now.
+	pub fn now(
+		office: &str,
+		number: Option<Phone>,
+		outcome: Outcome,
+	) -> Result<Call, ::time::error::IndeterminateOffset> {
+		let now = OffsetDateTime::now_local()?;
+		// This cannot panic! since the time of day of `now` is valid
+		let time = ::time::Time::from_hms(now.hour(), now.minute(), 0)
+			.unwrap();
+		let time = PrimitiveDateTime::new(now.date(), time);
+		Ok(Call::new(time, office, number, outcome))
+	}
+
+	pub fn office(&self) -> &str {
+		&self.office
+	}
+
+	pub fn outcome(&self) -> Outcome {
+		self.outcome
+	}
+
+	/// The call as a line of the log, without the line break.
+	pub fn line(&self) -> String {
+		let number = self.number.as_ref().map(Phone::e164);
+		format!(
+			"{}\t{}\t{}\t{}",
+			format_time(&self.time),
+			self.office,
+			number.unwrap_or_default(),
+			self.outcome,
+		)
+	}
+}
+
+/// The time as in the log, e.g. `"2026-10-19 14:03"`.
+fn format_time(time: &PrimitiveDateTime) -> String {
+	format!(
+		"{}-{:02}-{:02} {:02}:{:02}",
+		time.year(),
+		u8::from(time.month()),
+		time.day(),
+		time.hour(),
+		time.minute(),
+	)
+}
+
+/// Parses a time as in the log, e.g. `"2026-10-19 14:03"`.
+fn parse_time(src: &str) -> Option<PrimitiveDateTime> {
+	let (date, clock) = src.split_once(' ')?;
+	let mut date = date.splitn(3, '-');
+	let year = date.next()?.parse().ok()?;
+	let month = Month::try_from(date.next()?.parse::<u8>().ok()?).ok()?;
+	let day = date.next()?.parse().ok()?;
+	let date = Date::from_calendar_date(year, month, day).ok()?;
+	let (hours, minutes) = clock.split_once(':')?;
+	let (hours, minutes) = (hours.parse().ok()?, minutes.parse().ok()?);
+	let clock = ::time::Time::from_hms(hours, minutes, 0).ok()?;
+	Some(PrimitiveDateTime::new(date, clock))
+}
+
+impl FromStr for Call {
+	type Err = CallErr;
+
+	/// Parses a line of the log.
+	fn from_str(src: &str) -> Result<Call, Self::Err> {
+		let fields: Vec<&str> = src.split('\t').collect();
+		let [time, office, number, outcome] = fields[..] else {
+			return Err(CallErr::MissingField);
+		};
+		let time = parse_time(time).ok_or(CallErr::InvalidTime)?;
+		let number = match number {
+			"" => None,
+			number => Some(
+				Phone::from_str(number)
+					.map_err(|_| CallErr::InvalidNumber)?,
+			),
+		};
+		let outcome = Outcome::from_str(outcome)
+			.map_err(|_| CallErr::InvalidOutcome)?;
+		Ok(Call::new(time, office, number, outcome))
+	}
+}
+
+impl fmt::Display for Call {
+	/// Writes the call for people, i.e. without the office.
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{} {}", format_time(&self.time), self.outcome)?;
+		match &self.number {
+			Some(number) => write!(fmt, " ({})", number),
+			None => Ok(()),
+		}
+	}
+}
+
+/// A line of the log that could not be parsed.
+#[derive(Debug, PartialEq, Eq)]
+pub struct CallLogErr {
+	line: usize,
+	kind: CallErr,
+}
+
+impl fmt::Display for CallLogErr {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "line {}: ", self.line)?;
+		match self.kind {
+			CallErr::MissingField => {
+				write!(fmt, "expected four fields")
+			}
+			CallErr::InvalidTime => write!(fmt, "invalid time"),
+			CallErr::InvalidNumber => write!(fmt, "invalid number"),
+			CallErr::InvalidOutcome => {
+				write!(fmt, "unknown outcome")
+			}
+		}
+	}
+}
+
+/// The calls of the log, oldest first.
+#[derive(Debug, Default)]
+pub struct CallLog {
+	data: Vec<Call>,
+}
+
+impl CallLog {
+	/// The calls to the office with the given id.
+	pub fn of(&self, office: &str) -> CallLog {
+		let data = self.data.iter().filter(|x| x.office == office);
+		CallLog { data: data.cloned().collect() }
+	}
+
+	/// The last call to the office with the given id, if any.
+	pub fn last(&self, office: &str) -> Option<&Call> {
+		self.data.iter().rev().find(|x| x.office == office)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.data.is_empty()
+	}
+
+	/// The ids of the offices called that none of the offices has, e.g.
+	/// because the names of an office changed while it had no `Id:` line.
+	pub fn unknown_offices(&self, offices: &Offices) -> Vec<&str> {
+		let mut ids: Vec<&str> = Vec::new();
+		for call in &self.data {
+			let id = call.office.as_str();
+			if !ids.contains(&id) && offices.by_id(id).is_none() {
+				ids.push(id);
+			}
+		}
+		ids
+	}
+
+	/// Parses a log, ignoring empty lines and skipping the lines that
+	/// cannot be parsed, which are returned as well.
+	pub fn recovering(src: &str) -> (CallLog, Vec<CallLogErr>) {
+		let mut data = Vec::new();
+		let mut errors = Vec::new();
+		for (index, line) in src.lines().enumerate() {
+			if line.trim().is_empty() {
+				continue;
+			}
+			match Call::from_str(line) {
+				Ok(call) => data.push(call),
+				Err(kind) => {
+					let line = index + 1;
+					errors.push(CallLogErr { line, kind });
+				}
+			}
+		}
+		(CallLog { data }, errors)
+	}
+}
+
+impl FromStr for CallLog {
+	type Err = CallLogErr;
+
+	/// Parses a log, ignoring empty lines but failing at the first line
+	/// that cannot be parsed.
+	fn from_str(src: &str) -> Result<CallLog, Self::Err> {
+		let (log, errors) = CallLog::recovering(src);
+		match errors.into_iter().next() {
+			Some(error) => Err(error),
+			None => Ok(log),
+		}
+	}
+}
+
+impl fmt::Display for CallLog {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		for call in &self.data {
+			writeln!(fmt, "{}", call)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn outcomes() {
+		for outcome in OUTCOMES {
+			let parsed = Outcome::from_str(&outcome.to_string());
+			assert_eq!(parsed.unwrap(), outcome);
+		}
+		let rueckruf = Outcome::RueckrufZugesagt;
+		assert_eq!(Outcome::from_str("rückruf").unwrap(), rueckruf);
+		let keine = Outcome::KeineAntwort;
+		assert_eq!(Outcome::from_str("keine-antwort").unwrap(), keine);
+		assert_eq!(Outcome::from_str("ab").unwrap(), Outcome::AB);
+		assert!(Outcome::from_str("verwählt").is_err());
+	}
+
+	#[test]
+	fn log() {
+		let input = "2026-10-19 09:05\tallissat-prawitz\t\tbesetzt\n\n\
+			2026-10-19 14:03\thegel\t+496151951196\tAB\n\
+			2026-10-20 08:30\tallissat-prawitz\t\tWarteliste\n";
+		let log = CallLog::from_str(input).unwrap();
+		let last = log.last("allissat-prawitz").unwrap();
+		assert_eq!(last.outcome(), Outcome::Warteliste);
+		let expected = "2026-10-19 09:05 besetzt\n\
+			2026-10-20 08:30 Warteliste\n";
+		assert_eq!(log.of("allissat-prawitz").to_string(), expected);
+		let expected = "2026-10-19 14:03 AB (06151 951196)";
+		assert_eq!(log.of("hegel").data[0].to_string(), expected);
+		let line = "2026-10-19 14:03\thegel\t+496151951196\tAB";
+		assert_eq!(log.data[1].line(), line);
+		let error = CallLog::from_str("2026-10-19 14:03\thegel\tAB");
+		assert_eq!(error.unwrap_err().to_string(), "line 1: expected \
+			four fields");
+	}
+
+	#[test]
+	fn recovering() {
+		let input = "2026-10-19 09:05\thegel\t\tbesetzt\n\
+			2026-10-19 14:03\thegel\tAB\n\
+			2026-10-20 08:30\thegel\t\tverwählt\n\
+			2026-10-20 09:30\tallissat-prawitz\t\tAB\n";
+		let (log, errors) = CallLog::recovering(input);
+		assert_eq!(log.data.len(), 2);
+		let errors: Vec<String> =
+			errors.iter().map(ToString::to_string).collect();
+		let expected = [
+			"line 2: expected four fields",
+			"line 3: unknown outcome",
+		];
+		assert_eq!(errors, expected);
+		let offices = crate::hrdb::offices("Hegel\n06151 111\n");
+		let offices = offices.unwrap();
+		assert_eq!(log.unknown_offices(&offices), ["allissat-prawitz"]);
+	}
+
+	#[test]
+	fn slugs() {
+		let expected = "ingrid-allissat-iris-prawitz";
+		assert_eq!(slug("Ingrid Allißat, Iris Prawitz"), expected);
+		let expected = "dr-michele-keil-hujer";
+		assert_eq!(slug("Dr. Michèle Keil-Hujer"), expected);
+	}
+}
@@ -0,0 +1,215 @@
+//! Ways of contacting an office other than by phone.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// An email address.
+///
+/// The validation is not a full implementation of RFC 5322 but catches the
+/// usual typos: the address needs exactly one `@`, a local part without
+/// whitespace and a domain with at least two non-empty labels.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Email {
+	local: String,
+	domain: String,
+}
+
+#[derive(Debug)]
+pub enum EmailErr {
+	MissingAt,
+	InvalidLocalPart,
+	InvalidDomain,
+}
+
+/// Whether a string is a valid domain name (of at least two labels).
+///
+/// Labels may contain any alphanumeric characters, so that internationalized
+/// domains like those with umlauts are accepted.
+fn is_domain(src: &str) -> bool {
+	let valid_char = |c: char| c.is_alphanumeric() || c == '-';
+	let valid_label = |label: &str| {
+		!label.is_empty()
+			&& !label.starts_with('-')
+			&& !label.ends_with('-')
+			&& label.chars().all(valid_char)
+	};
+	src.contains('.') && src.split('.').all(valid_label)
+}
+
+impl FromStr for Email {
+	type Err = EmailErr;
+
+	fn from_str(src: &str) -> Result<Email, Self::Err> {
+		let (local, domain) =
+			src.split_once('@').ok_or(EmailErr::MissingAt)?;
+		let invalid_char = |c: char| c.is_whitespace() || c == '@';
+		let local_valid =
+			!local.is_empty() && !local.chars().any(invalid_char);
+		if !local_valid {
+			Err(EmailErr::InvalidLocalPart)
+		} else if !is_domain(domain) {
+			Err(EmailErr::InvalidDomain)
+		} else {
+			let local = String::from(local);
+			let domain = String::from(domain);
+			Ok(Email { local, domain })
+		}
+	}
+}
+
+impl fmt::Display for Email {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}@{}", self.local, self.domain)
+	}
+}
+
+/// The address of a website.
+///
+/// Addresses may be given without a scheme, in which case HTTPS is assumed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Website {
+	scheme: String,
+	/// The host and everything following it.
+	rest: String,
+}
+
+#[derive(Debug)]
+pub enum WebsiteErr {
+	UnknownScheme,
+	InvalidHost,
+}
+
+impl FromStr for Website {
+	type Err = WebsiteErr;
+
+	fn from_str(src: &str) -> Result<Website, Self::Err> {
+		let (scheme, rest) = match src.split_once("://") {
+			Some((scheme, rest)) => (scheme, rest),
+			None => ("https", src),
+		};
+		if scheme != "http" && scheme != "https" {
+			return Err(WebsiteErr::UnknownScheme);
+		}
+		let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+		let host = &rest[..end];
+		if !is_domain(host) || rest.chars().any(char::is_whitespace) {
+			return Err(WebsiteErr::InvalidHost);
+		}
+		let scheme = String::from(scheme);
+		let rest = String::from(rest);
+		Ok(Website { scheme, rest })
+	}
+}
+
+impl fmt::Display for Website {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}://{}", self.scheme, self.rest)
+	}
+}
+
+/// A German postal address.
+///
+/// Addresses are written on a single line as street and house number,
+/// a comma and a space and then postal code and city, as in
+/// `"Rheinstraße 1, 64283 Darmstadt"`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Address {
+	street: String,
+	postal_code: String,
+	city: String,
+}
+
+#[derive(Debug)]
+pub enum AddressErr {
+	MissingComma,
+	InvalidPostalCode,
+	MissingCity,
+}
+
+impl Address {
+	pub fn street(&self) -> &str {
+		&self.street
+	}
+
+	pub fn postal_code(&self) -> &str {
+		&self.postal_code
+	}
+
+	pub fn city(&self) -> &str {
+		&self.city
+	}
+}
+
+impl FromStr for Address {
+	type Err = AddressErr;
+
+	fn from_str(src: &str) -> Result<Address, Self::Err> {
+		let (street, place) =
+			src.rsplit_once(", ").ok_or(AddressErr::MissingComma)?;
+		let (postal_code, city) = place
+			.split_once(' ')
+			.ok_or(AddressErr::MissingCity)?;
+		let valid_code = postal_code.len() == 5
+			&& postal_code.chars().all(|c| c.is_ascii_digit());
+		if !valid_code {
+			Err(AddressErr::InvalidPostalCode)
+		} else if street.is_empty() || city.is_empty() {
+			Err(AddressErr::MissingCity)
+		} else {
+			let street = String::from(street);
+			let postal_code = String::from(postal_code);
+			let city = String::from(city);
+			Ok(Address { street, postal_code, city })
+		}
+	}
+}
+
+impl fmt::Display for Address {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		let Address { street, postal_code, city } = self;
+		write!(fmt, "{}, {} {}", street, postal_code, city)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn email() {
+		let email = Email::from_str("praxis@müller-voss.de").unwrap();
+		assert_eq!(email.to_string(), "praxis@müller-voss.de");
+		assert!(Email::from_str("praxis.example.org").is_err());
+		assert!(Email::from_str("@example.org").is_err());
+		assert!(Email::from_str("pra xis@example.org").is_err());
+		assert!(Email::from_str("praxis@example").is_err());
+		assert!(Email::from_str("praxis@example..org").is_err());
+	}
+
+	#[test]
+	fn website() {
+		let web = Website::from_str("www.example.org/praxis").unwrap();
+		assert_eq!(web.to_string(), "https://www.example.org/praxis");
+		let web = Website::from_str("http://example.org").unwrap();
+		assert_eq!(web.to_string(), "http://example.org");
+		assert!(Website::from_str("ftp://example.org").is_err());
+		assert!(Website::from_str("example").is_err());
+	}
+
+	#[test]
+	fn address() {
+		let address = "Rheinstraße 1, 64283 Darmstadt";
+		let address = Address::from_str(address).unwrap();
+		assert_eq!(address.street(), "Rheinstraße 1");
+		assert_eq!(address.postal_code(), "64283");
+		assert_eq!(address.city(), "Darmstadt");
+		let invalid = [
+			"Rheinstraße 1 64283 Darmstadt",
+			"Rheinstraße 1, 6428 Darmstadt",
+			"Rheinstraße 1, 64283",
+		];
+		for address in invalid {
+			assert!(Address::from_str(address).is_err());
+		}
+	}
+}
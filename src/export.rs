@@ -0,0 +1,174 @@
+//! Exporting offices into formats understood by other software.
+
+use std::fmt;
+
+use crate::phone::PhoneLabel;
+use crate::{Office, Offices};
+
+/// Escape a text value for use in a vCard.
+fn escape(src: &str) -> String {
+	let mut escaped = String::with_capacity(src.len());
+	for c in src.chars() {
+		match c {
+			'\\' | ',' | ';' => {
+				escaped.push('\\');
+				escaped.push(c);
+			}
+			'\n' => escaped.push_str("\\n"),
+			_ => escaped.push(c),
+		}
+	}
+	escaped
+}
+
+/// The maximal length of a line of a vCard in octets, without the line
+/// break (RFC 6350, section 3.2).
+const MAX_LINE_LEN: usize = 75;
+
+/// Write a content line of a vCard, folding it into lines of at most
+/// `MAX_LINE_LEN` octets.
+///
+/// Continuation lines start with a space. Lines are only broken between
+/// characters, so that no UTF-8 sequence is split.
+fn content_line(fmt: &mut fmt::Formatter, line: &str) -> fmt::Result {
+	let mut len = 0;
+	for c in line.chars() {
+		if len + c.len_utf8() > MAX_LINE_LEN {
+			write!(fmt, "\r\n ")?;
+			len = 1;
+		}
+		write!(fmt, "{}", c)?;
+		len += c.len_utf8();
+	}
+	write!(fmt, "\r\n")
+}
+
+/// Write a single office as a vCard.
+fn vcard(office: &Office, fmt: &mut fmt::Formatter) -> fmt::Result {
+	let name = escape(&office.names.to_string());
+	content_line(fmt, "BEGIN:VCARD")?;
+	content_line(fmt, "VERSION:4.0")?;
+	content_line(fmt, &format!("FN:{}", name))?;
+	if let Some(first) = office.names.first() {
+		let n = format!(
+			"N:{};{};;{};",
+			escape(&first.family_name()),
+			escape(&first.given_names()),
+			escape(&first.titles().join(" ")),
+		);
+		content_line(fmt, &n)?;
+	}
+	for phone in &office.phones.data {
+		let kind = if phone.has_label(&PhoneLabel::Mobil) {
+			"cell"
+		} else {
+			"work"
+		};
+		let uri = phone.tel_uri();
+		let tel = format!("TEL;VALUE=uri;TYPE={},voice:{}", kind, uri);
+		content_line(fmt, &tel)?;
+	}
+	for email in &office.emails {
+		content_line(fmt, &format!("EMAIL;TYPE=work:{}", email))?;
+	}
+	for website in &office.websites {
+		content_line(fmt, &format!("URL:{}", website))?;
+	}
+	for address in &office.addresses {
+		let adr = format!(
+			"ADR;TYPE=work:;;{};{};;{};Deutschland",
+			escape(address.street()),
+			escape(address.city()),
+			escape(address.postal_code()),
+		);
+		content_line(fmt, &adr)?;
+	}
+	if !office.tags.data.is_empty() {
+		let tags: Vec<String> =
+			office.tags.data.iter().map(|x| escape(x)).collect();
+		content_line(fmt, &format!("CATEGORIES:{}", tags.join(",")))?;
+	}
+	let comments = office.comments.data.iter().map(|x| x.data.as_str());
+	let notes = office.notes.iter().map(|x| x.data.as_str());
+	let notes: Vec<&str> = comments.chain(notes).collect();
+	if !notes.is_empty() {
+		let note = format!("NOTE:{}", escape(&notes.join("\n")));
+		content_line(fmt, &note)?;
+	}
+	content_line(fmt, "END:VCARD")
+}
+
+/// The offices as vCards (version 4.0), one per office, e.g. for importing
+/// them into the address book of a phone.
+///
+/// Phone numbers are written as `tel:` URIs (see `Phone::tel_uri`), so that
+/// they can be dialed as they are from anywhere.
+pub struct VCards<'a> {
+	offices: &'a Offices,
+}
+
+impl<'a> VCards<'a> {
+	pub fn new(offices: &'a Offices) -> VCards<'a> {
+		VCards { offices }
+	}
+}
+
+impl fmt::Display for VCards<'_> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		for office in &self.offices.data {
+			vcard(office, fmt)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::hrdb;
+
+	#[test]
+	fn vcard() {
+		let input = "Ingrid Allißat, Iris Prawitz\n\
+			0615161544 (Praxis), 01701234567 (Mobil)\n\
+			Email: praxis@example.org\n\
+			Adresse: Rheinstraße 1, 64283 Darmstadt\n\
+			Tags: VT, Kinder\n\
+			# Erstmal per Mail anschreiben";
+		let offices = hrdb::offices(input).unwrap();
+		let expected = "BEGIN:VCARD\r\nVERSION:4.0\r\n\
+			FN:Ingrid Allißat\\, Iris Prawitz\r\n\
+			N:Allißat;Ingrid;;;\r\n\
+			TEL;VALUE=uri;TYPE=work,voice:tel:+49615161544\r\n\
+			TEL;VALUE=uri;TYPE=cell,voice:tel:+491701234567\r\n\
+			EMAIL;TYPE=work:praxis@example.org\r\n\
+			ADR;TYPE=work:;;Rheinstraße 1;Darmstadt;;64283;\
+			Deutschland\r\n\
+			CATEGORIES:Kinder,VT\r\n\
+			NOTE:Erstmal per Mail anschreiben\r\n\
+			END:VCARD\r\n";
+		assert_eq!(VCards::new(&offices).to_string(), expected);
+	}
+
+	#[test]
+	fn folding() {
+		let input = "Name\n0123\n\
+			# Bitte nur dienstags anrufen, montags ist die Praxis \
+			für Gruppen reserviert\n\
+			# Rückrufe meist noch am selben Tag, oft aber erst \
+			spät abends. Übrigens auch samstags";
+		let offices = hrdb::offices(input).unwrap();
+		let vcard = VCards::new(&offices).to_string();
+		let lines: Vec<&str> = vcard.split("\r\n").collect();
+		assert!(lines.iter().all(|x| x.len() <= MAX_LINE_LEN));
+		let note = "NOTE:Bitte nur dienstags anrufen\\, montags ist \
+			die Praxis für Gruppen rese";
+		assert_eq!(note.len(), MAX_LINE_LEN);
+		// The "Ü" would end after the 75th octet, so it starts a line
+		let next = " rviert\\nRückrufe meist noch am selben Tag\\, \
+			oft aber erst spät abends. ";
+		assert_eq!(next.len(), MAX_LINE_LEN - 1);
+		let last = " Übrigens auch samstags";
+		assert_eq!(lines[5..8], [note, next, last]);
+	}
+}
@@ -0,0 +1,97 @@
+//! Locating offices, to know how far they are away.
+//!
+//! Everything works offline: an office is located by the coordinates of its
+//! `Ort:` line only. Its `Adresse:` is not geocoded, since that would need
+//! a table of all postal codes or an online service, so offices without an
+//! `Ort:` cannot be located and are reported by `hrdb::lint` if they have
+//! an address.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The mean radius of the earth in kilometres.
+const EARTH_RADIUS: f64 = 6371.0;
+
+/// A point on the earth, in degrees of latitude and longitude (WGS 84).
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Coordinates {
+	lat: f64,
+	lon: f64,
+}
+
+#[derive(Debug)]
+pub enum CoordinatesErr {
+	MissingComma,
+	InvalidNumber,
+	OutOfRange,
+}
+
+impl Coordinates {
+	/// The great-circle distance to the other point in kilometres, by the
+	/// haversine formula.
+	pub fn distance(&self, other: &Coordinates) -> f64 {
+		let lat1 = self.lat.to_radians();
+		let lat2 = other.lat.to_radians();
+		let d_lat = lat2 - lat1;
+		let d_lon = (other.lon - self.lon).to_radians();
+		let a = (d_lat / 2.0).sin().powi(2)
+			+ lat1.cos() * lat2.cos() * (d_lon / 2.0).sin().powi(2);
+		2.0 * EARTH_RADIUS * a.sqrt().asin()
+	}
+}
+
+impl FromStr for Coordinates {
+	type Err = CoordinatesErr;
+
+	/// Parses latitude and longitude separated by a comma, e.g.
+	/// `"49.8728, 8.6512"`.
+	fn from_str(src: &str) -> Result<Coordinates, Self::Err> {
+		let (lat, lon) = src
+			.split_once(',')
+			.ok_or(CoordinatesErr::MissingComma)?;
+		let number = |x: &str| {
+			let number = x.trim().parse::<f64>().ok();
+			number
+				.filter(|x| x.is_finite())
+				.ok_or(CoordinatesErr::InvalidNumber)
+		};
+		let (lat, lon) = (number(lat)?, number(lon)?);
+		if lat.abs() > 90.0 || lon.abs() > 180.0 {
+			Err(CoordinatesErr::OutOfRange)
+		} else {
+			Ok(Coordinates { lat, lon })
+		}
+	}
+}
+
+impl fmt::Display for Coordinates {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}, {}", self.lat, self.lon)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parsing() {
+		let point = Coordinates::from_str("49.8728, 8.6512").unwrap();
+		assert_eq!(point.to_string(), "49.8728, 8.6512");
+		assert!(Coordinates::from_str("49.8728 8.6512").is_err());
+		assert!(Coordinates::from_str("49.8728, Ost").is_err());
+		assert!(Coordinates::from_str("98.6512, 49.8728").is_err());
+		assert!(Coordinates::from_str("NaN, NaN").is_err());
+		assert!(Coordinates::from_str("inf, 8.6512").is_err());
+	}
+
+	#[test]
+	fn distance() {
+		let point = |x| Coordinates::from_str(x).unwrap();
+		let darmstadt = point("49.8728, 8.6512");
+		let frankfurt = point("50.1109, 8.6821");
+		let km = darmstadt.distance(&frankfurt);
+		assert!((km - 26.6).abs() < 0.1, "{}", km);
+		assert_eq!(darmstadt.distance(&darmstadt), 0.0);
+	}
+}
@@ -0,0 +1,249 @@
+//! An offline calendar of the German public holidays.
+//!
+//! Apart from the holidays common to all of Germany, most holidays are
+//! decided by the individual states, so the calendar always needs to know
+//! which state it is about. Holidays that are only observed in parts of a
+//! state (like Mariä Himmelfahrt in the catholic communities of Bavaria or
+//! the Augsburger Friedensfest) are not included.
+
+use std::fmt;
+use std::str::FromStr;
+
+use time::{Date, Duration, Month, Weekday};
+
+/// A German state, identified by its official two-letter abbreviation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum State {
+	BW,
+	BY,
+	BE,
+	BB,
+	HB,
+	HH,
+	HE,
+	MV,
+	NI,
+	NW,
+	RP,
+	SL,
+	SN,
+	ST,
+	SH,
+	TH,
+}
+
+/// All states together with their abbreviation and full name.
+const STATES: [(State, &str, &str); 16] = [
+	(State::BW, "BW", "Baden-Württemberg"),
+	(State::BY, "BY", "Bayern"),
+	(State::BE, "BE", "Berlin"),
+	(State::BB, "BB", "Brandenburg"),
+	(State::HB, "HB", "Bremen"),
+	(State::HH, "HH", "Hamburg"),
+	(State::HE, "HE", "Hessen"),
+	(State::MV, "MV", "Mecklenburg-Vorpommern"),
+	(State::NI, "NI", "Niedersachsen"),
+	(State::NW, "NW", "Nordrhein-Westfalen"),
+	(State::RP, "RP", "Rheinland-Pfalz"),
+	(State::SL, "SL", "Saarland"),
+	(State::SN, "SN", "Sachsen"),
+	(State::ST, "ST", "Sachsen-Anhalt"),
+	(State::SH, "SH", "Schleswig-Holstein"),
+	(State::TH, "TH", "Thüringen"),
+];
+
+#[derive(Debug)]
+pub enum StateErr {
+	Unknown,
+}
+
+impl FromStr for State {
+	type Err = StateErr;
+
+	/// Accepts both the abbreviation and the full name of a state.
+	fn from_str(src: &str) -> Result<State, Self::Err> {
+		STATES
+			.iter()
+			.find(|(_, abbr, name)| src == *abbr || src == *name)
+			.map(|(state, _, _)| *state)
+			.ok_or(StateErr::Unknown)
+	}
+}
+
+impl fmt::Display for State {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		// This cannot panic! since every state is listed in `STATES`
+		let (_, _, name) =
+			STATES.iter().find(|(s, _, _)| s == self).unwrap();
+		write!(fmt, "{}", name)
+	}
+}
+
+/// A single public holiday.
+#[derive(Debug, Clone)]
+pub struct Holiday {
+	date: Date,
+	name: &'static str,
+}
+
+impl Holiday {
+	pub fn date(&self) -> &Date {
+		&self.date
+	}
+}
+
+impl fmt::Display for Holiday {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}", self.name)
+	}
+}
+
+/// The date of Easter Sunday in the Gregorian calendar.
+///
+/// This is the anonymous Gregorian algorithm (also known as the
+/// Meeus/Jones/Butcher algorithm) and valid for all years of the Gregorian
+/// calendar.
+pub fn easter_sunday(year: i32) -> Date {
+	let a = year.rem_euclid(19);
+	let b = year.div_euclid(100);
+	let c = year.rem_euclid(100);
+	let d = b / 4;
+	let e = b % 4;
+	let f = (b + 8) / 25;
+	let g = (b - f + 1) / 3;
+	let h = (19 * a + b - d - g + 15) % 30;
+	let i = c / 4;
+	let k = c % 4;
+	let l = (32 + 2 * e + 2 * i - h - k) % 7;
+	let m = (a + 11 * h + 22 * l) / 451;
+	let month = (h + l - 7 * m + 114) / 31;
+	let day = (h + l - 7 * m + 114) % 31 + 1;
+	let month = if month == 3 { Month::March } else { Month::April };
+	// This cannot panic! since Easter is always between 22.03. and 25.04.
+	Date::from_calendar_date(year, month, day as u8).unwrap()
+}
+
+/// The Buß- und Bettag, i.e. the last Wednesday before the 23rd of November.
+fn buss_und_bettag(year: i32) -> Option<Date> {
+	let mut date =
+		Date::from_calendar_date(year, Month::November, 22).ok()?;
+	while date.weekday() != Weekday::Wednesday {
+		date = date.previous_day()?;
+	}
+	Some(date)
+}
+
+/// All public holidays of a year in the given state, in no particular order.
+pub fn holidays(year: i32, state: State) -> Vec<Holiday> {
+	use State::*;
+
+	let mut holidays = Vec::new();
+	let mut add = |date: Option<Date>, name: &'static str| {
+		if let Some(date) = date {
+			holidays.push(Holiday { date, name });
+		}
+	};
+	let fixed = |month: Month, day: u8| {
+		Date::from_calendar_date(year, month, day).ok()
+	};
+	let easter = easter_sunday(year);
+	let movable = |days: i64| easter.checked_add(Duration::days(days));
+
+	add(fixed(Month::January, 1), "Neujahr");
+	add(movable(-2), "Karfreitag");
+	add(movable(1), "Ostermontag");
+	add(fixed(Month::May, 1), "Tag der Arbeit");
+	add(movable(39), "Christi Himmelfahrt");
+	add(movable(50), "Pfingstmontag");
+	add(fixed(Month::October, 3), "Tag der Deutschen Einheit");
+	add(fixed(Month::December, 25), "1. Weihnachtstag");
+	add(fixed(Month::December, 26), "2. Weihnachtstag");
+
+	if matches!(state, BW | BY | ST) {
+		add(fixed(Month::January, 6), "Heilige Drei Könige");
+	}
+	if (state == BE && year >= 2019) || (state == MV && year >= 2023) {
+		add(fixed(Month::March, 8), "Internationaler Frauentag");
+	}
+	if state == BB {
+		add(Some(easter), "Ostersonntag");
+		add(movable(49), "Pfingstsonntag");
+	}
+	if state == BE && (year == 2020 || year == 2025) {
+		add(fixed(Month::May, 8), "Tag der Befreiung");
+	}
+	if matches!(state, BW | BY | HE | NW | RP | SL) {
+		add(movable(60), "Fronleichnam");
+	}
+	if state == SL {
+		add(fixed(Month::August, 15), "Mariä Himmelfahrt");
+	}
+	if state == TH && year >= 2019 {
+		add(fixed(Month::September, 20), "Weltkindertag");
+	}
+	let reformation = match state {
+		BB | MV | SN | ST | TH => true,
+		HB | HH | NI | SH => year >= 2018,
+		_ => year == 2017,
+	};
+	if reformation {
+		add(fixed(Month::October, 31), "Reformationstag");
+	}
+	if matches!(state, BW | BY | NW | RP | SL) {
+		add(fixed(Month::November, 1), "Allerheiligen");
+	}
+	if state == SN {
+		add(buss_und_bettag(year), "Buß- und Bettag");
+	}
+	holidays
+}
+
+/// The public holiday on the given date in the given state, if there is one.
+pub fn holiday(date: &Date, state: State) -> Option<Holiday> {
+	holidays(date.year(), state)
+		.into_iter()
+		.find(|h| h.date == *date)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ymd(year: i32, month: Month, day: u8) -> Date {
+		Date::from_calendar_date(year, month, day).unwrap()
+	}
+
+	#[test]
+	fn easter() {
+		assert_eq!(easter_sunday(2000), ymd(2000, Month::April, 23));
+		assert_eq!(easter_sunday(2008), ymd(2008, Month::March, 23));
+		assert_eq!(easter_sunday(2026), ymd(2026, Month::April, 5));
+		assert_eq!(easter_sunday(2038), ymd(2038, Month::April, 25));
+	}
+
+	#[test]
+	fn state_from_str() {
+		assert_eq!(State::from_str("HE").unwrap(), State::HE);
+		assert_eq!(State::from_str("Hessen").unwrap(), State::HE);
+		assert!(State::from_str("Hesse").is_err());
+	}
+
+	#[test]
+	fn hessen() {
+		let easter_monday = ymd(2026, Month::April, 6);
+		let corpus_christi = ymd(2026, Month::June, 4);
+		let reformation_day = ymd(2026, Month::October, 31);
+		assert!(holiday(&easter_monday, State::HE).is_some());
+		assert!(holiday(&corpus_christi, State::HE).is_some());
+		assert!(holiday(&reformation_day, State::HE).is_none());
+		let anniversary = ymd(2017, Month::October, 31);
+		assert!(holiday(&anniversary, State::HE).is_some());
+	}
+
+	#[test]
+	fn sachsen() {
+		let repentance_day = ymd(2026, Month::November, 18);
+		assert!(holiday(&repentance_day, State::SN).is_some());
+		assert!(holiday(&repentance_day, State::HE).is_none());
+	}
+}
@@ -2,9 +2,11 @@
 //! hours into the internal datatypes of this crate using Nom.
 //!
 //! The format is human writable enough for me but not very lenient, it expects
-//! exact adherence to its not-always-obvious for everyone layout and does not
-//! give sensible error messages so far. This is not expected to change unless
-//! other people start using the software.
+//! exact adherence to its not-always-obvious for everyone layout (apart from
+//! the usual whitespace mess left by other editors, see `normalize`) and does
+//! not give sensible error messages so far. This is not expected to change
+//! unless other people start using the software. It does, however, at least
+//! tell in which line things went wrong and carries on with the next office.
 //!
 //! Also, the format is based on German shorthands and conventions, which, too,
 //! is not expected to change unless the programm – against my honest
@@ -35,9 +37,14 @@
 use nom;
 use nom::IResult;
 
-use super::time::{Clock, Day, OfficeHour};
+use super::time::{
+	Absence, Channel, Clock, Day, ExtraHour, OfficeHour, Recurrence,
+};
 use super::*;
 
+use ::time::{Date, Month};
+
+use std::fmt;
 use std::str::FromStr;
 
 /// Nom parser for a list of names.
@@ -58,32 +65,94 @@ fn names(input: &str) -> IResult<&str, Names> {
 	)(input)
 }
 
+/// Nom parser for a group of digits in a phone number, possibly enclosed in
+/// parentheses like the area code in `"(06151) 374815"`.
+fn phone_digits(input: &str) -> IResult<&str, &str> {
+	nom::branch::alt((
+		nom::character::complete::digit1,
+		nom::combinator::recognize(nom::sequence::delimited(
+			nom::bytes::complete::tag("("),
+			nom::character::complete::digit1,
+			nom::bytes::complete::tag(")"),
+		)),
+	))(input)
+}
+
 /// Nom parser for a single phone number.
 ///
-/// Phone numbers are currenly expected to use only decimal digits
-/// and no other symbols.
+/// Phone numbers may start with a plus and consist of groups of digits
+/// separated by single spaces, slashes or dashes, see `Phone` for the
+/// notations understood.
 fn phone_number(input: &str) -> IResult<&str, Phone> {
-	nom::combinator::map(
-		nom::character::complete::digit1,
-		|s: &str| Phone::from_str(&s.as_ref()).unwrap(),
+	nom::combinator::map_res(
+		nom::combinator::recognize(nom::sequence::tuple((
+			nom::combinator::opt(nom::bytes::complete::tag("+")),
+			phone_digits,
+			nom::multi::many0(nom::sequence::preceded(
+				nom::combinator::opt(
+					nom::character::complete::one_of(" /-"),
+				),
+				phone_digits,
+			)),
+		))),
+		Phone::from_str,
 	)(input)
 }
 
+/// Nom parser for a phone number optionally followed by a space and its
+/// label in parentheses, e.g. `"06151713962 (Praxis)"`.
+fn labelled_phone_number(input: &str) -> IResult<&str, Phone> {
+	let (input, mut phone) = phone_number(input)?;
+	let (input, label) = nom::combinator::opt(nom::sequence::delimited(
+		nom::bytes::complete::tag(" ("),
+		nom::combinator::map_res(
+			nom::bytes::complete::is_not(")\n"),
+			PhoneLabel::from_str,
+		),
+		nom::bytes::complete::tag(")"),
+	))(input)?;
+	if let Some(label) = label {
+		phone.add_label(label);
+	}
+	Ok((input, phone))
+}
+
 /// Nom parser for a comma-and-space separated list of phone numbers.
 fn phone_numbers(input: &str) -> IResult<&str, Phones> {
 	nom::combinator::map(
 		nom::multi::separated_list0(
 			nom::bytes::complete::tag(", "),
-			phone_number,
+			labelled_phone_number,
 		),
 		Phones::from,
 	)(input)
 }
 
+/// Nom parser for the dash separating the endpoints of a range.
+///
+/// The dash should be surrounded by a space on either side and may either be
+/// a proper en dash or, for the lazy typist, a hyphen.
+fn dash(input: &str) -> IResult<&str, &str> {
+	nom::branch::alt((
+		nom::bytes::complete::tag(" – "),
+		nom::bytes::complete::tag(" - "),
+	))(input)
+}
+
+/// Nom parser for the comma separating the items of a list, optionally
+/// surrounded by spaces, e.g. in `"VT, Kinder"` or `"VT,Kinder"`.
+fn comma(input: &str) -> IResult<&str, &str> {
+	nom::sequence::delimited(
+		nom::character::complete::space0,
+		nom::bytes::complete::tag(","),
+		nom::character::complete::space0,
+	)(input)
+}
+
 /// Nom parser for a single day of the week.
 ///
 /// The parser expects the day to be encoded by its standard German shorthand.
-fn day(input: &str) -> IResult<&str, Day> {
+pub(crate) fn day(input: &str) -> IResult<&str, Day> {
 	nom::branch::alt((
 		nom::combinator::value(
 			Day::Mon,
@@ -110,12 +179,12 @@ fn day(input: &str) -> IResult<&str, Day> {
 
 /// Nom parser to parse a single day into a list of days.
 fn single_day(day: Day) -> Vec<Day> {
-	let mut days = Vec::new();
-	days.push(day);
-	days
+	vec![day]
 }
 
 /// Turning two days into the list of days between them (endpoints included).
+///
+/// The first day must not come after the last one.
 fn days_from_range(begin: Day, end: Day) -> Vec<Day> {
 	let mut days = single_day(begin.clone());
 	let mut day = begin;
@@ -130,7 +199,8 @@ fn days_from_range(begin: Day, end: Day) -> Vec<Day> {
 /// Nom parser to parse a range of days into a list.
 ///
 /// A range may be specified by two days separated by a hyphen surround by a
-/// space on either side.
+/// space on either side. Ranges wrapping around the weekend, like
+/// `"Fr – Mo"`, are not supported.
 ///
 /// # Example
 ///
@@ -138,9 +208,10 @@ fn days_from_range(begin: Day, end: Day) -> Vec<Day> {
 /// day_range("Mo - Fr") ≈ Ok(("", vec![Day::Mon, Day::Tue, Day::Wed]));
 /// ```
 fn day_range(input: &str) -> IResult<&str, Vec<Day>> {
-	let (input, begin) = day(input)?;
-	let (input, _) = nom::bytes::complete::tag(" – ")(input)?;
-	let (input, end) = day(input)?;
+	let (input, (begin, end)) = nom::combinator::verify(
+		nom::sequence::separated_pair(day, dash, day),
+		|(begin, end)| begin <= end,
+	)(input)?;
 	Ok((input, days_from_range(begin, end)))
 }
 
@@ -211,42 +282,149 @@ fn days(input: &str) -> IResult<&str, Vec<Day>> {
 /// Expects the input to be a decimal representation of an integer betwenn
 /// 0 and 255 (inclusive).
 fn small_number(input: &str) -> IResult<&str, u8> {
-	nom::combinator::map(
+	nom::combinator::map_res(
 		nom::character::complete::digit1,
-		|str: &str| FromStr::from_str(str).unwrap(),
+		u8::from_str,
 	)(input)
 }
 
 /// Nom parser for a time specification.
 ///
 /// Expects the input to be of the form HH:MM in a 24 hour format.
-fn time(input: &str) -> IResult<&str, Clock> {
-	let (input, hours) = small_number(input)?;
-	let (input, _) = nom::bytes::complete::tag(":")(input)?;
-	let (input, minutes) = small_number(input)?;
-	Ok((input, Clock::new(hours, minutes)))
+pub(crate) fn time(input: &str) -> IResult<&str, Clock> {
+	nom::combinator::map_res(
+		nom::sequence::separated_pair(
+			small_number,
+			nom::bytes::complete::tag(":"),
+			small_number,
+		),
+		|(hours, minutes)| Clock::new(hours, minutes),
+	)(input)
 }
 
 /// Nom parser for a time range.
 ///
 /// Expects the input to be a pair of times separated by space-dash-space
 /// and returns the pair of these two times-of-day.
+///
+/// An end before the beginning means the range crosses midnight. A range
+/// beginning and ending at the same time or beginning at 24:00 is ambiguous
+/// and therefore rejected.
 fn time_pair(input: &str) -> IResult<&str, (Clock, Clock)> {
-	nom::combinator::map(
+	nom::combinator::verify(
+		nom::combinator::map(
+			nom::sequence::tuple((time, dash, time)),
+			|(a, _, b)| (a, b),
+		),
+		|(a, b)| a != b && !a.is_end_of_day(),
+	)(input)
+}
+
+/// Nom parser for a date.
+///
+/// Expects the input to be of the form DD.MM.YYYY, as is customary in Germany,
+/// and to denote a date that actually exists.
+fn date(input: &str) -> IResult<&str, Date> {
+	nom::combinator::map_opt(
+		nom::sequence::tuple((
+			nom::character::complete::u8,
+			nom::bytes::complete::tag("."),
+			nom::character::complete::u8,
+			nom::bytes::complete::tag("."),
+			nom::character::complete::u16,
+		)),
+		|(day, _, month, _, year)| {
+			let month = Month::try_from(month).ok()?;
+			Date::from_calendar_date(year.into(), month, day).ok()
+		},
+	)(input)
+}
+
+/// Nom parser for the recurrence of office hours that do not take place
+/// every week.
+///
+/// The recurrence is given in parentheses and may be one of
+/// `"(gerade Wochen)"` or `"(ungerade Wochen)"` for even or odd calendar
+/// weeks, `"(2. im Monat)"` or `"(letzter im Monat)"` for the second or last
+/// of the days in a month and `"(alle 3 Wochen ab 07.10.2026)"` for every
+/// third week, starting with the week containing the given date.
+fn recurrence(input: &str) -> IResult<&str, Recurrence> {
+	let nth_of_month = nom::combinator::map(
+		nom::sequence::terminated(
+			nom::combinator::verify(
+				nom::character::complete::u8,
+				|n| (1..=5).contains(n),
+			),
+			nom::bytes::complete::tag(". im Monat"),
+		),
+		Recurrence::NthOfMonth,
+	);
+	let every_n_weeks = nom::combinator::map(
 		nom::sequence::tuple((
-			time,
-			nom::bytes::complete::tag(" - "),
-			time,
+			nom::bytes::complete::tag("alle "),
+			nom::combinator::verify(
+				nom::character::complete::u8,
+				|n| *n > 0,
+			),
+			nom::bytes::complete::tag(" Wochen ab "),
+			date,
+		)),
+		|(_, n, _, anchor)| Recurrence::EveryNWeeks(n, anchor),
+	);
+	nom::sequence::delimited(
+		nom::bytes::complete::tag("("),
+		nom::branch::alt((
+			nom::combinator::value(
+				Recurrence::EvenWeeks,
+				nom::bytes::complete::tag("gerade Wochen"),
+			),
+			nom::combinator::value(
+				Recurrence::OddWeeks,
+				nom::bytes::complete::tag("ungerade Wochen"),
+			),
+			nom::combinator::value(
+				Recurrence::LastOfMonth,
+				nom::bytes::complete::tag("letzter im Monat"),
+			),
+			nth_of_month,
+			every_n_weeks,
 		)),
-		|(a, _, b)| (a, b),
+		nom::bytes::complete::tag(")"),
 	)(input)
 }
 
+/// Nom parser for a comma-and-space separated list of time ranges.
+fn time_pairs(input: &str) -> IResult<&str, Vec<(Clock, Clock)>> {
+	nom::multi::separated_list1(
+		nom::bytes::complete::tag(", "),
+		time_pair,
+	)(input)
+}
+
+/// Nom parser for the channel by which an office can be reached, given by
+/// its German name followed by a space, e.g. `"Sprechstunde "`.
+///
+/// Lines without a channel are about phone calls.
+fn channel(input: &str) -> IResult<&str, Channel> {
+	let (input, channel) = nom::combinator::opt(nom::sequence::terminated(
+		nom::combinator::map_res(
+			nom::character::complete::alpha1,
+			Channel::from_str,
+		),
+		nom::bytes::complete::tag(" "),
+	))(input)?;
+	Ok((input, channel.unwrap_or_default()))
+}
+
 /// Turns a list of days-of-week and list of pairs of times-of-day
 /// into the corresponding list of `OfficeHour`s.
 fn office_hours_from_days_and_times(
 	days: Vec<Day>,
 	times: Vec<(Clock, Clock)>,
+	recurrence: Option<Recurrence>,
+	channel: Channel,
+	phone: Option<Phone>,
+	person: Option<Name>,
 ) -> Vec<OfficeHour> {
 	let mut ranges = Vec::with_capacity(days.len() * times.len());
 	for day in days {
@@ -255,72 +433,372 @@ fn office_hours_from_days_and_times(
 				day.clone(),
 				begin.clone(),
 				end.clone(),
+				recurrence.clone(),
+				channel,
+				phone.clone(),
+				person.clone(),
 			));
 		}
 	}
 	ranges
 }
 
+/// Nom parser for the phone answered during some hours, given by `" @ "`
+/// and one of the phone numbers of the office, e.g. `" @ 06151713962"`.
+///
+/// Hours without such a phone are answered on all phones of the office.
+/// The label of the phone is not repeated here.
+fn answered_phone<'a>(
+	input: &'a str,
+	office: &Office,
+) -> IResult<&'a str, Option<Phone>> {
+	nom::combinator::opt(nom::sequence::preceded(
+		nom::bytes::complete::tag(" @ "),
+		nom::combinator::cut(nom::combinator::map_opt(
+			phone_number,
+			|phone| office.phones.find(&phone).cloned(),
+		)),
+	))(input)
+}
+
+/// Nom parser for the person answering during some hours, given by a space
+/// and one of the names of the office in parentheses, e.g.
+/// `" (Iris Prawitz)"`.
+///
+/// Hours without such a person are answered by the whole office.
+fn answering_person<'a>(
+	input: &'a str,
+	office: &Office,
+) -> IResult<&'a str, Option<Name>> {
+	nom::combinator::opt(nom::sequence::preceded(
+		nom::bytes::complete::tag(" ("),
+		nom::combinator::cut(nom::sequence::terminated(
+			nom::combinator::map_opt(
+				nom::bytes::complete::is_not(")\n"),
+				|name| office.names.find(name).cloned(),
+			),
+			nom::bytes::complete::tag(")"),
+		)),
+	))(input)
+}
+
 /// Nom parser modifying an `Office` by adding the `OfficeHour`s specified
 /// by the input.
 ///
-/// The office hours should be specified by specifying the days, then a colon
-/// and a space and then specifying the time ranges common to these days.
+/// The office hours should be specified by optionally giving their channel,
+/// then specifying the days, optionally followed by a space and their
+/// recurrence, then a colon and a space and
+/// then specifying the time ranges common to these days, optionally followed
+/// by the phone answered during them (see `answered_phone`) and by the
+/// person answering (see `answering_person`).
 /// When not all days have the same time ranges, multiple such specifications
 /// must be made on separate lines to be merged by a higher level parser.
+///
+/// Once the days and the colon have been parsed, invalid time ranges are
+/// a failure instead of an error, so that these lines do not silently end up
+/// as comments.
 fn add_times<'a>(
 	input: &'a str,
 	office: &mut Office,
 ) -> nom::IResult<&'a str, ()> {
-	let (input, (_, days, _, times)) = nom::sequence::tuple((
-		nom::bytes::complete::tag("\n"),
+	let (input, (_, channel, days, recurrence, _, times)) =
+		nom::sequence::tuple((
+			nom::bytes::complete::tag("\n"),
+			channel,
+			days,
+			nom::combinator::opt(nom::sequence::preceded(
+				nom::bytes::complete::tag(" "),
+				recurrence,
+			)),
+			nom::bytes::complete::tag(": "),
+			nom::combinator::cut(time_pairs),
+		))(input)?;
+	let (input, phone) = answered_phone(input, office)?;
+	let (input, person) = answering_person(input, office)?;
+	let hours = office_hours_from_days_and_times(
 		days,
+		times,
+		recurrence,
+		channel,
+		phone,
+		person,
+	);
+	Ok((input, office.add_times(hours)))
+}
+
+/// Nom parser modifying an `Office` by adding the `ExtraHour`s specified
+/// by the input.
+///
+/// Extra hours are specified by an optional channel, `"am "`, the date,
+/// a colon and a space, the time ranges on that date and optionally the
+/// phone answered and the person answering, as for regular office hours,
+/// e.g. `"am 23.10.2026: 14:00 – 15:00"`. Since offices cannot be reached on
+/// the weekend (see `Day`), a date on a weekend is a failure.
+fn add_extra_hours<'a>(
+	input: &'a str,
+	office: &mut Office,
+) -> IResult<&'a str, ()> {
+	let weekday = |x: &Date| Day::try_from(x.weekday()).is_ok();
+	let (input, (_, channel, _, date, _, times)) = nom::sequence::tuple((
+		nom::bytes::complete::tag("\n"),
+		channel,
+		nom::bytes::complete::tag("am "),
+		nom::combinator::cut(nom::combinator::verify(date, weekday)),
 		nom::bytes::complete::tag(": "),
-		nom::multi::separated_list1(
-			nom::bytes::complete::tag(", "),
-			time_pair,
-		),
+		nom::combinator::cut(time_pairs),
 	))(input)?;
-	// TODO: This line is over 80 characters in width.
-	Ok((input, office.add_times(office_hours_from_days_and_times(days, times))))
+	let (input, phone) = answered_phone(input, office)?;
+	let (input, person) = answering_person(input, office)?;
+	let hours = times
+		.into_iter()
+		.map(|(begin, end)| {
+			let (phone, person) = (phone.clone(), person.clone());
+			ExtraHour::new(date, begin, end, channel, phone, person)
+		})
+		.collect();
+	Ok((input, office.add_extra_hours(hours)))
+}
+
+/// Nom parser modifying an `Office` by starting a new schedule, to which all
+/// following office hours belong.
+///
+/// The period of validity of the schedule is given by `"ab "` and a date,
+/// by `"bis "` and a date or by two dates separated by a dash, and followed
+/// by a colon, e.g. `"ab 01.11.2026:"`.
+fn start_schedule<'a>(
+	input: &'a str,
+	office: &mut Office,
+) -> IResult<&'a str, ()> {
+	let (input, _) = nom::bytes::complete::tag("\n")(input)?;
+	let (input, (from, until)) = nom::sequence::terminated(
+		nom::branch::alt((
+			nom::combinator::map(
+				nom::sequence::preceded(
+					nom::bytes::complete::tag("ab "),
+					date,
+				),
+				|from| (Some(from), None),
+			),
+			nom::combinator::map(
+				nom::sequence::preceded(
+					nom::bytes::complete::tag("bis "),
+					date,
+				),
+				|until| (None, Some(until)),
+			),
+			nom::combinator::map(
+				nom::sequence::separated_pair(date, dash, date),
+				|(from, until)| (Some(from), Some(until)),
+			),
+		)),
+		nom::bytes::complete::tag(":"),
+	)(input)?;
+	Ok((input, office.start_schedule(from, until)))
+}
+
+/// Nom parser modifying an `Office` by adding the `Absence` specified
+/// by the input.
+///
+/// An absence is introduced by `"Urlaub: "` followed either by a single date
+/// or by a range of dates separated by a dash, e.g.
+/// `"Urlaub: 01.08.2026 – 15.08.2026"`. A range ending before it begins is a
+/// failure.
+fn add_absence<'a>(
+	input: &'a str,
+	office: &mut Office,
+) -> IResult<&'a str, ()> {
+	let (input, _) = nom::bytes::complete::tag("\nUrlaub: ")(input)?;
+	let (input, begin) = nom::combinator::cut(date)(input)?;
+	let (input, end) = nom::combinator::cut(nom::combinator::verify(
+		nom::combinator::opt(nom::sequence::preceded(dash, date)),
+		|end: &Option<Date>| end.is_none_or(|end| end >= begin),
+	))(input)?;
+	let end = end.unwrap_or(begin);
+	Ok((input, office.add_absence(Absence::new(begin, end))))
 }
 
 /// Nom parser modifying an `Office` by adding the `Comment` specified
 /// by the input.
 ///
-/// A comment is any valid UTF-8 string not containing new lines and not
-/// obeying the formatting rules for office hour specifications.
+/// A comment is a line starting with `"#"`, the comment itself is the rest
+/// of the line (without the space following the `"#"`, if any).
 fn add_comment<'a>(
 	input: &'a str,
 	office: &mut Office,
 ) -> IResult<&'a str, ()> {
-	let (input, _) = nom::bytes::complete::tag("\n")(input)?;
+	let (input, _) = nom::bytes::complete::tag("\n#")(input)?;
+	let (input, _) = nom::combinator::opt(
+		nom::bytes::complete::tag(" "),
+	)(input)?;
 	let (input, comment) = nom::combinator::map(
-			nom::bytes::complete::is_not("\n"),
+			nom::bytes::complete::take_till(|c| c == '\n'),
 			Comment::from,
 		)(input)?;
 	Ok((input, office.add_comment(comment)))
 }
 
-/// Nom parser modifying an `Office` by adding the `OfficeHour`s or `Comment`
-/// specified by the input.
+/// Nom parser for the key of a metadata line.
+///
+/// A key is a single capitalized word, possibly containing hyphens.
+fn metadata_key(input: &str) -> IResult<&str, &str> {
+	nom::combinator::recognize(nom::sequence::pair(
+		nom::character::complete::satisfy(char::is_uppercase),
+		nom::bytes::complete::take_while(|c: char| {
+			c.is_alphabetic() || c == '-'
+		}),
+	))(input)
+}
+
+/// Nom parser for the value of a metadata line of a known type.
+///
+/// Since the key has already been recognized, an invalid value is a failure.
+fn metadata_value<T: FromStr>(input: &str) -> IResult<&str, T> {
+	nom::combinator::cut(nom::combinator::map_res(
+		nom::bytes::complete::is_not("\n"),
+		T::from_str,
+	))(input)
+}
+
+/// Nom parser for a list of tags separated by commas, e.g.
+/// `"VT, Kinder, Kassensitz"`, or of similar short texts like languages.
+fn tags(input: &str) -> IResult<&str, Vec<&str>> {
+	nom::multi::separated_list1(
+		comma,
+		nom::combinator::map(
+			nom::bytes::complete::is_not(",\n"),
+			str::trim,
+		),
+	)(input)
+}
+
+/// Nom parser for a list of `Insurance`s separated by commas, e.g.
+/// `"GKV, PKV"`.
+fn insurances(input: &str) -> IResult<&str, Vec<Insurance>> {
+	nom::multi::separated_list1(
+		comma,
+		nom::combinator::map_res(
+			nom::bytes::complete::is_not(",\n"),
+			|x: &str| Insurance::from_str(x.trim()),
+		),
+	)(input)
+}
+
+/// Nom parser modifying an `Office` by adding the metadata specified
+/// by the input.
+///
+/// A metadata line consists of a key, a colon and a space and the value,
+/// e.g. `"Email: praxis@example.org"`. The known keys are `"Email"` (or
+/// `"E-Mail"`), `"Web"`, `"Adresse"`, `"Ort"` (the coordinates of the
+/// office, e.g. `"49.8728, 8.6512"`), `"Id"` (overriding `Office::id`,
+/// which it needs to be written like), `"Notiz"`, `"Tags"` and those
+/// compared with a `Profile`, i.e. `"Kasse"`, `"Verfahren"`, `"Alter"` and
+/// `"Sprachen"`. Others are kept as they are and reported by `lint`.
+fn add_metadata<'a>(
+	input: &'a str,
+	office: &mut Office,
+) -> IResult<&'a str, ()> {
+	let (input, (_, key, _)) = nom::sequence::tuple((
+		nom::bytes::complete::tag("\n"),
+		metadata_key,
+		nom::bytes::complete::tag(": "),
+	))(input)?;
+	match key {
+		"Email" | "E-Mail" => {
+			let (input, email) = metadata_value(input)?;
+			Ok((input, office.add_email(email)))
+		}
+		"Web" => {
+			let (input, website) = metadata_value(input)?;
+			Ok((input, office.add_website(website)))
+		}
+		"Adresse" => {
+			let (input, address) = metadata_value(input)?;
+			Ok((input, office.add_address(address)))
+		}
+		"Id" => {
+			let (input, id) = nom::combinator::cut(
+				nom::combinator::verify(
+					nom::bytes::complete::is_not("\n"),
+					|x: &str| calls::slug(x) == x,
+				),
+			)(input)?;
+			Ok((input, office.set_id(id)))
+		}
+		"Ort" => {
+			let (input, location) = metadata_value(input)?;
+			Ok((input, office.set_location(location)))
+		}
+		"Notiz" => {
+			let (input, note) = nom::combinator::map(
+				nom::bytes::complete::is_not("\n"),
+				Note::from,
+			)(input)?;
+			Ok((input, office.add_note(note)))
+		}
+		"Tags" => {
+			let (input, tags) = nom::combinator::cut(tags)(input)?;
+			Ok((input, office.add_tags(tags)))
+		}
+		"Kasse" => {
+			let (input, insurances) =
+				nom::combinator::cut(insurances)(input)?;
+			Ok((input, office.add_insurances(insurances)))
+		}
+		"Verfahren" => {
+			let (input, methods) =
+				nom::combinator::cut(tags)(input)?;
+			Ok((input, office.add_methods(methods)))
+		}
+		"Alter" => {
+			let (input, groups) =
+				nom::combinator::cut(tags)(input)?;
+			Ok((input, office.add_age_groups(groups)))
+		}
+		"Sprachen" => {
+			let (input, languages) =
+				nom::combinator::cut(tags)(input)?;
+			Ok((input, office.add_languages(languages)))
+		}
+		_ => {
+			let (input, value) =
+				nom::bytes::complete::is_not("\n")(input)?;
+			let metadata = Metadata::new(key, value);
+			Ok((input, office.add_unknown(metadata)))
+		}
+	}
+}
+
+/// The type of the Nom parsers modifying an `Office` by a single line.
+type AddInfo = for<'a> fn(&'a str, &mut Office) -> IResult<&'a str, ()>;
+
+/// Nom parser modifying an `Office` by adding the `OfficeHour`s,
+/// `ExtraHour`s, schedule, `Absence`, `Comment` or metadata specified by the
+/// input.
 fn add_info<'a>(
 	input: &'a str,
 	office: &mut Office,
 ) -> nom::IResult<&'a str, ()> {
-	// TODO: Why is this not a `nom::combinator::alt` application?
-	if let Ok((rest, _)) = add_times(input, office) {
-		Ok((rest, ()))
-	} else if let Ok((rest, _)) = add_comment(input, office) {
-		Ok((rest, ()))
-	} else {
-		use nom::*;
-		// FIXME: `ErrorKind::Tag` is not the correct error
-		Err(Err::Error(nom::error::Error {
-			input,
-			code: nom::error::ErrorKind::Tag,
-		}))
+	// This is not a `nom::branch::alt` application since all the
+	// alternatives need to borrow the office mutably.
+	let alternatives: [AddInfo; 6] = [
+		add_times,
+		add_extra_hours,
+		start_schedule,
+		add_absence,
+		add_comment,
+		add_metadata,
+	];
+	for alternative in alternatives {
+		match alternative(input, office) {
+			Err(nom::Err::Error(_)) => continue,
+			res => return res,
+		}
 	}
+	// FIXME: `ErrorKind::Tag` is not the correct error
+	Err(nom::Err::Error(nom::error::Error {
+		input,
+		code: nom::error::ErrorKind::Tag,
+	}))
 }
 
 /// Nom parser for the basic information of an `Office`.
@@ -342,36 +820,278 @@ fn base_office(input: &str) -> IResult<&str, Office> {
 /// lines and then any number of lines with office hours or comments. An
 /// office ends with the first empty line after the first two.
 fn office(input: &str) -> IResult<&str, Office> {
-	let (mut input, mut office) = base_office(input).unwrap();
+	let (mut input, mut office) = base_office(input)?;
 	loop {
-		let res = add_info(input, &mut office);
-		if let Ok((rest, _)) = res {
-			input = rest;
-		} else {
-			break;
+		match add_info(input, &mut office) {
+			Ok((rest, _)) => input = rest,
+			Err(nom::Err::Error(_)) => break,
+			Err(e) => return Err(e),
 		}
 	}
 	Ok((input, office))
 }
 
-/// Nom parser for a list of `Office`s.
+/// Nom parser for the holiday calendar of a database.
 ///
-/// Offices should be separated by a single empty line.
-// TODO: Should this insist on parsing an EOF at the end?
-pub fn offices(input: &str) -> IResult<&str, Offices> {
-	nom::combinator::map(
-		nom::multi::separated_list0(
-			nom::bytes::complete::tag("\n\n"),
-			office,
-		),
-		Offices::from
+/// The calendar is selected by a line `"Feiertage: "` followed by a German
+/// state, either by its abbreviation or its full name, e.g.
+/// `"Feiertage: Hessen"`, and separated from the offices by an empty line.
+fn holiday_calendar(input: &str) -> IResult<&str, State> {
+	nom::sequence::delimited(
+		nom::bytes::complete::tag("Feiertage: "),
+		nom::combinator::cut(nom::combinator::map_res(
+			nom::bytes::complete::is_not("\n"),
+			State::from_str,
+		)),
+		office_end,
 	)(input)
 }
 
+/// Nom parser for the end of an office, i.e. either the empty line
+/// separating it from the next office or the end of the input (possibly
+/// after the final line break).
+///
+/// Any further empty lines are skipped as well.
+fn office_end(input: &str) -> IResult<&str, ()> {
+	let line_breaks = || nom::bytes::complete::take_while(|c| c == '\n');
+	nom::branch::alt((
+		nom::combinator::value(
+			(),
+			nom::sequence::pair(
+				nom::bytes::complete::tag("\n\n"),
+				line_breaks(),
+			),
+		),
+		nom::combinator::value(
+			(),
+			nom::sequence::pair(
+				line_breaks(),
+				nom::combinator::eof,
+			),
+		),
+	))(input)
+}
+
+/// The kinds of `Problem`s that can occur while parsing.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ProblemKind {
+	/// The choice of the holiday calendar could not be parsed.
+	InvalidHolidayCalendar,
+	/// An office could not be parsed.
+	InvalidOffice,
+	/// An office was followed by something other than an empty line or the
+	/// end of the input.
+	UnexpectedInput,
+}
+
+impl fmt::Display for ProblemKind {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		let description = match self {
+			ProblemKind::InvalidHolidayCalendar => {
+				"invalid holiday calendar"
+			}
+			ProblemKind::InvalidOffice => "invalid office",
+			ProblemKind::UnexpectedInput => "unexpected input",
+		};
+		write!(fmt, "{}", description)
+	}
+}
+
+/// A problem found while parsing, located by the line in which it occured.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Problem {
+	/// The number of the line, counting from 1.
+	line: usize,
+	/// The contents of the line.
+	text: String,
+	kind: ProblemKind,
+}
+
+impl Problem {
+	/// Locate a problem in the `input` at the start of its suffix `rest`.
+	///
+	/// Since most parsers start by parsing the line break ending the
+	/// previous line, a problem right at a line break is located in the
+	/// next line.
+	fn new(input: &str, rest: &str, kind: ProblemKind) -> Problem {
+		let mut offset = input.len() - rest.len();
+		if rest.starts_with('\n') {
+			offset += 1;
+		}
+		let before = &input[..offset];
+		let line = before.matches('\n').count() + 1;
+		let begin = before.rfind('\n').map_or(0, |i| i + 1);
+		let end = input[offset..]
+			.find('\n')
+			.map_or(input.len(), |i| offset + i);
+		let text = String::from(&input[begin..end]);
+		Problem { line, text, kind }
+	}
+
+	pub fn line(&self) -> usize {
+		self.line
+	}
+
+	pub fn kind(&self) -> &ProblemKind {
+		&self.kind
+	}
+}
+
+impl fmt::Display for Problem {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "line {}: {}: {}", self.line, self.kind, self.text)
+	}
+}
+
+/// The remaining input at which a Nom parser gave up.
+fn error_input<'a>(
+	rest: &'a str,
+	err: nom::Err<nom::error::Error<&'a str>>,
+) -> &'a str {
+	match err {
+		nom::Err::Error(e) | nom::Err::Failure(e) => e.input,
+		nom::Err::Incomplete(_) => rest,
+	}
+}
+
+/// Skip the rest of the current office, continuing after the next empty
+/// lines.
+fn skip_office(input: &str) -> &str {
+	match input.find("\n\n") {
+		Some(i) => input[i..].trim_start_matches('\n'),
+		None => "",
+	}
+}
+
+/// Normalize the whitespace of a database to what the parsers expect.
+///
+/// This removes a leading byte order mark, turns Windows and old Mac line
+/// breaks into plain line feeds and removes trailing whitespace from every
+/// line. The number of lines does not change, so that line numbers of
+/// problems still refer to the original input.
+pub fn normalize(input: &str) -> String {
+	let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+	let input = input.replace("\r\n", "\n").replace('\r', "\n");
+	let lines: Vec<&str> = input.split('\n').map(str::trim_end).collect();
+	lines.join("\n")
+}
+
+/// Parse a list of `Office`s, carrying on after broken offices.
+///
+/// The list may be preceded by the choice of a holiday calendar.
+/// Offices should be separated by empty lines. When an office cannot
+/// be parsed, it is skipped up to the next empty line and the problem is
+/// recorded. The result consists of all offices that could be parsed and
+/// all problems encountered.
+///
+/// The input is normalized first, so problems refer to the normalized lines.
+pub fn offices_recovering(input: &str) -> (Offices, Vec<Problem>) {
+	let normalized = normalize(input);
+	let input = normalized.as_str();
+	let mut problems = Vec::new();
+	let start = input.trim_start_matches('\n');
+	let calendar = nom::combinator::opt(holiday_calendar)(start);
+	let (mut rest, holidays) = match calendar {
+		Ok(res) => res,
+		Err(err) => {
+			let at = error_input(start, err);
+			let kind = ProblemKind::InvalidHolidayCalendar;
+			problems.push(Problem::new(input, at, kind));
+			(skip_office(at), None)
+		}
+	};
+	let mut data = Vec::new();
+	while !rest.is_empty() {
+		let (after, office) = match office(rest) {
+			Ok(res) => res,
+			Err(err) => {
+				let at = error_input(rest, err);
+				let kind = ProblemKind::InvalidOffice;
+				problems.push(Problem::new(input, at, kind));
+				rest = skip_office(at);
+				continue;
+			}
+		};
+		match office_end(after) {
+			Ok((after, _)) => {
+				data.push(office);
+				rest = after;
+			}
+			Err(_) => {
+				let kind = ProblemKind::UnexpectedInput;
+				problems.push(Problem::new(input, after, kind));
+				rest = skip_office(after);
+			}
+		}
+	}
+	(Offices::new(data, holidays), problems)
+}
+
+/// A suspicious but valid piece of an office.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Lint {
+	/// The names of the office.
+	office: String,
+	message: String,
+}
+
+impl fmt::Display for Lint {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}: {}", self.office, self.message)
+	}
+}
+
+/// Look for suspicious pieces in parsed offices, namely metadata with keys
+/// unknown to the parser, which are likely typos, ids shared by several
+/// offices, which would mix up their calls in the call log, and addresses
+/// without an `Ort:`, without which the office cannot be located (see
+/// `geo`).
+pub fn lint(offices: &Offices) -> Vec<Lint> {
+	let mut lints = Vec::new();
+	for (i, office) in offices.data.iter().enumerate() {
+		for metadata in &office.unknown {
+			let key = &metadata.key;
+			let message = format!("unknown key \"{}\"", key);
+			let office = office.names.to_string();
+			lints.push(Lint { office, message });
+		}
+		let id = office.id();
+		if offices.data[..i].iter().any(|x| x.id() == id) {
+			let message = format!("id \"{}\" is not unique", id);
+			let office = office.names.to_string();
+			lints.push(Lint { office, message });
+		}
+		if !office.addresses.is_empty() && office.location.is_none() {
+			let message = String::from("address without Ort:");
+			let office = office.names.to_string();
+			lints.push(Lint { office, message });
+		}
+	}
+	lints
+}
+
+/// Parse a list of `Office`s, insisting on all of the input being valid.
+///
+/// See `offices_recovering` for the format, the result is the first problem
+/// encountered, if any.
+pub fn offices(input: &str) -> Result<Offices, Problem> {
+	let (offices, problems) = offices_recovering(input);
+	match problems.into_iter().next() {
+		Some(problem) => Err(problem),
+		None => Ok(offices),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 
+	const PHONE: Channel = Channel::Phone;
+
+	fn no_holiday(_: &Date) -> bool {
+		false
+	}
+
 	#[test]
 	fn test_day() {
 		let (_, res) = day("Mo").unwrap();
@@ -402,6 +1122,431 @@ mod tests {
 	#[test]
 	fn test_time() {
 		let (_, res) = time("10:38").unwrap();
-		assert_eq!(res, Clock::new(10, 38));
+		assert_eq!(res, clock(10, 38));
+	}
+
+	#[test]
+	fn test_malformed() {
+		assert!(small_number("300").is_err());
+		assert!(time("25:61").is_err());
+		assert!(day_range("Fr – Mo").is_err());
+		let input = "Name\n0123\nMo: 25:00 – 26:00";
+		assert!(matches!(office(input), Err(nom::Err::Failure(_))));
+		let input = "Name\n0123\nUrlaub: 31.02.2026";
+		assert!(matches!(office(input), Err(nom::Err::Failure(_))));
+		let inputs = ["", "\n", "\n\n\n", ", ", "Name\n\n\nFr – Mo"];
+		for input in inputs {
+			let _ = offices(input);
+		}
+	}
+
+	#[test]
+	fn test_end_of_day() {
+		let (_, res) = time("24:00").unwrap();
+		assert_eq!(res, clock(24, 0));
+	}
+
+	#[test]
+	fn test_overnight() {
+		let input = "Name\n0123\nMo – Fr: 20:00 – 8:00";
+		let (_, office) = office(input).unwrap();
+		let time = |day, hours| {
+			let date = ymd(2026, Month::October, day);
+			Time::new(date, clock(hours, 0))
+		};
+		// The 19th of October 2026 is a Monday
+		assert!(!office.reachable(&time(19, 3), &PHONE, no_holiday));
+		assert!(office.reachable(&time(19, 21), &PHONE, no_holiday));
+		assert!(office.reachable(&time(20, 3), &PHONE, no_holiday));
+		assert!(!office.reachable(&time(20, 12), &PHONE, no_holiday));
+	}
+
+	#[test]
+	fn test_time_pair() {
+		let (_, res) = time_pair("8:20 – 8:50").unwrap();
+		let expected = (clock(8, 20), clock(8, 50));
+		assert_eq!(res, expected);
+		let (_, res) = time_pair("8:20 - 8:50").unwrap();
+		assert_eq!(res, expected);
+		assert!(time_pair("9:00 – 9:00").is_err());
+		assert!(time_pair("24:00 – 2:00").is_err());
+		let input = "Name\n0123\nMo: 9:00 – 9:00";
+		assert!(matches!(office(input), Err(nom::Err::Failure(_))));
+	}
+
+	fn clock(hours: u8, minutes: u8) -> Clock {
+		Clock::new(hours, minutes).unwrap()
+	}
+
+	fn ymd(year: i32, month: Month, day: u8) -> Date {
+		Date::from_calendar_date(year, month, day).unwrap()
+	}
+
+	#[test]
+	fn test_date() {
+		let (_, res) = date("01.08.2026").unwrap();
+		assert_eq!(res, ymd(2026, Month::August, 1));
+		assert!(date("31.02.2026").is_err());
+	}
+
+	#[test]
+	fn test_absence() {
+		let (_, office) =
+			office("Name\n0123\nUrlaub: 01.08.2026 – 15.08.2026")
+				.unwrap();
+		let absences = &office.absences;
+		assert!(absences.contain(&ymd(2026, Month::August, 3)));
+		assert!(!absences.contain(&ymd(2026, Month::August, 16)));
+		assert!(office.comments.data.is_empty());
+		let input = "Name\n0123\nUrlaub: 15.08.2026 – 01.08.2026";
+		let reversed = super::office(input);
+		assert!(matches!(reversed, Err(nom::Err::Failure(_))));
+	}
+
+	#[test]
+	fn test_recurrence() {
+		let (_, res) = recurrence("(gerade Wochen)").unwrap();
+		assert_eq!(res, Recurrence::EvenWeeks);
+		let (_, res) = recurrence("(1. im Monat)").unwrap();
+		assert_eq!(res, Recurrence::NthOfMonth(1));
+		let input = "(alle 3 Wochen ab 07.10.2026)";
+		let (_, res) = recurrence(input).unwrap();
+		let anchor = ymd(2026, Month::October, 7);
+		assert_eq!(res, Recurrence::EveryNWeeks(3, anchor));
+		assert!(recurrence("(0. im Monat)").is_err());
+		assert!(recurrence("(alle 0 Wochen ab 07.10.2026)").is_err());
+	}
+
+	#[test]
+	fn test_times_with_recurrence() {
+		let input = "Name\n0123\nMi (ungerade Wochen): 9:00 – 10:00";
+		let (_, office) = office(input).unwrap();
+		assert!(office.comments.data.is_empty());
+		let time = |day| {
+			let date = ymd(2026, Month::October, day);
+			Time::new(date, clock(9, 30))
+		};
+		assert!(office.reachable(&time(21), &PHONE, no_holiday));
+		assert!(!office.reachable(&time(14), &PHONE, no_holiday));
+	}
+
+	#[test]
+	fn test_schedules() {
+		let input = "Name\n0123\nMo: 9:00 – 10:00\nab 01.11.2026:\n\
+			Di: 9:00 – 10:00";
+		let (_, office) = office(input).unwrap();
+		assert!(office.comments.data.is_empty());
+		let time = |month, day| {
+			let date = ymd(2026, month, day);
+			Time::new(date, clock(9, 30))
+		};
+		let reachable = |month, day| {
+			office.reachable(&time(month, day), &PHONE, no_holiday)
+		};
+		assert!(reachable(Month::October, 26));
+		assert!(!reachable(Month::October, 27));
+		assert!(!reachable(Month::November, 2));
+		assert!(reachable(Month::November, 3));
+	}
+
+	#[test]
+	fn test_extra_hours() {
+		let input = "Name\n0123\nam 23.10.2026: 14:00 – 15:00";
+		let (_, office) = office(input).unwrap();
+		assert!(office.comments.data.is_empty());
+		let time = |day| {
+			let date = ymd(2026, Month::October, day);
+			Time::new(date, clock(14, 30))
+		};
+		assert!(office.reachable(&time(23), &PHONE, no_holiday));
+		assert!(!office.reachable(&time(30), &PHONE, no_holiday));
+		// The 24th of October 2026 is a Saturday
+		let input = "Name\n0123\nam 24.10.2026: 14:00 – 15:00";
+		let saturday = super::office(input);
+		assert!(matches!(saturday, Err(nom::Err::Failure(_))));
+	}
+
+	#[test]
+	fn test_channels() {
+		let input = "Name\n0123\nSprechstunde Mo: 9:00 – 10:00\n\
+			Di: 9:00 – 10:00\n\
+			Video am 28.10.2026: 9:00 – 10:00";
+		let (_, office) = office(input).unwrap();
+		assert!(office.comments.data.is_empty());
+		let time = |day| {
+			let date = ymd(2026, Month::October, day);
+			Time::new(date, clock(9, 30))
+		};
+		let reachable = |day, channel| {
+			office.reachable(&time(day), &channel, no_holiday)
+		};
+		let consultation = Channel::Consultation;
+		assert!(!reachable(26, PHONE));
+		assert!(reachable(26, consultation));
+		assert!(reachable(27, PHONE));
+		assert!(!reachable(27, consultation));
+		assert!(!reachable(28, PHONE));
+		assert!(reachable(28, Channel::Video));
+	}
+
+	#[test]
+	fn test_answered_phones() {
+		let input = "Name\n0123, 0456\nMo: 9:00 – 10:00 @ 0456\n\
+			Di: 9:00 – 10:00";
+		let res = offices(input).unwrap();
+		let time = |day| {
+			let date = ymd(2026, Month::October, day);
+			Time::new(date, clock(9, 30))
+		};
+		let monday = res.filter_time(&time(26), &PHONE);
+		assert_eq!(monday.data[0].phones.to_string(), "0456");
+		let tuesday = res.filter_time(&time(27), &PHONE);
+		assert_eq!(tuesday.data[0].phones.to_string(), "0123, 0456");
+		let unknown = "Name\n0123\nMo: 9:00 – 10:00 @ 0456";
+		assert!(offices(unknown).is_err());
+	}
+
+	#[test]
+	fn test_phone_notations() {
+		let input = "Name\n\
+			(06151) 37 48 15 (Praxis), +49 1575 8390361\n\
+			Mo: 9:00 – 10:00 @ 06151/374815";
+		let (_, office) = office(input).unwrap();
+		let expected = "06151 374815 (Praxis), 01575 8390361";
+		assert_eq!(office.phones.to_string(), expected);
+		let date = ymd(2026, Month::October, 26);
+		let time = Time::new(date, clock(9, 30));
+		assert!(office.reachable(&time, &PHONE, no_holiday));
+	}
+
+	#[test]
+	fn test_find_by_phone() {
+		let input = "A\n06151 374815\n\nB\n06151 74815\n\n\
+			C\n01575 8390361\n\nD\n+33 1 42 68 12 34\n";
+		let res = offices(input).unwrap();
+		let names = |x: Offices| -> Vec<String> {
+			x.data.iter().map(|x| x.names.to_string()).collect()
+		};
+		assert_eq!(names(res.find_by_phone("+496151374815")), ["A"]);
+		assert_eq!(names(res.find_by_phone("74815")), ["A", "B"]);
+		assert_eq!(names(res.find_by_phone("8390361")), ["C"]);
+		// As shown by a French phone, but parsed as a German number
+		assert_eq!(names(res.find_by_phone("0142681234")), ["D"]);
+		assert_eq!(names(res.find_by_phone("+49 1575 8390361")), ["C"]);
+		assert!(res.find_by_phone("815").is_empty());
+		assert!(res.find_by_phone("06151 999999").is_empty());
+	}
+
+	#[test]
+	fn test_answering_persons() {
+		let input = "Ingrid Allißat, Iris Prawitz\n0615161544\n\
+			Mo: 9:00 – 10:00 (Iris Prawitz)\n\
+			Mo: 9:30 – 11:00 (Ingrid Allißat)\n\
+			am 27.10.2026: 9:00 – 10:00 @ 0615161544 \
+			(Iris Prawitz)\n\
+			Mi: 9:00 – 10:00";
+		let res = offices(input).unwrap();
+		let names = |day, hours| {
+			let date = ymd(2026, Month::October, day);
+			let time = Time::new(date, clock(hours, 45));
+			let res = res.filter_time(&time, &PHONE);
+			res.data[0].names.to_string()
+		};
+		assert_eq!(names(26, 9), "Ingrid Allißat, Iris Prawitz");
+		assert_eq!(names(26, 10), "Ingrid Allißat");
+		assert_eq!(names(27, 9), "Iris Prawitz");
+		assert_eq!(names(28, 9), "Ingrid Allißat, Iris Prawitz");
+		let unknown = "Name\n0123\nMo: 9:00 – 10:00 (Other)";
+		assert!(offices(unknown).is_err());
+	}
+
+	#[test]
+	fn test_phone_labels() {
+		let input = "Name\n0123 (Praxis), 0456 (ab), 0789 (Privat)\n\
+			Mo: 9:00 – 10:00 @ 0456";
+		let res = offices(input).unwrap();
+		let phones = &res.data[0].phones;
+		assert_eq!(phones.data[1].labels(), [PhoneLabel::AB]);
+		let expected = "0123 (Praxis), 0456 (AB), 0789 (Privat)";
+		assert_eq!(phones.to_string(), expected);
+		let date = ymd(2026, Month::October, 26);
+		let time = Time::new(date, clock(9, 30));
+		let now = res.filter_time(&time, &PHONE);
+		assert_eq!(now.data[0].phones.to_string(), "0456 (AB)");
+		assert!(now.hide_phones(&[PhoneLabel::AB]).data.is_empty());
+		let labels = [PhoneLabel::AB, PhoneLabel::Praxis];
+		let preferred = res.prefer_phones(&labels);
+		let expected = "0456 (AB), 0123 (Praxis), 0789 (Privat)";
+		assert_eq!(preferred.data[0].phones.to_string(), expected);
+	}
+
+	#[test]
+	fn test_extra_hours_on_holiday() {
+		// The 6th of April 2026 is Easter Monday
+		let input = "Feiertage: HE\n\nName\n0123\n\
+			Tgl: 14:00 – 15:00\nam 06.04.2026: 9:00 – 10:00";
+		let res = offices(input).unwrap();
+		let time = |hours| {
+			let date = ymd(2026, Month::April, 6);
+			Time::new(date, clock(hours, 30))
+		};
+		assert!(res.filter_time(&time(14), &PHONE).data.is_empty());
+		assert_eq!(res.filter_time(&time(9), &PHONE).data.len(), 1);
+	}
+
+	#[test]
+	fn test_overnight_hours_around_holiday() {
+		// The 6th of April 2026 is Easter Monday
+		let input = "Feiertage: HE\n\nName\n0123\n\
+			Mo, Di: 22:00 – 2:00";
+		let res = offices(input).unwrap();
+		let time = |day| {
+			let date = ymd(2026, Month::April, day);
+			Time::new(date, clock(1, 0))
+		};
+		assert!(res.filter_time(&time(7), &PHONE).data.is_empty());
+		assert_eq!(res.filter_time(&time(8), &PHONE).data.len(), 1);
+	}
+
+	#[test]
+	fn test_holiday_calendar() {
+		let input = "Feiertage: HE\n\nName\n0123\nTgl: 8:00 – 9:00";
+		let res = offices(input).unwrap();
+		assert_eq!(res.holidays, Some(State::HE));
+		assert_eq!(res.data.len(), 1);
+		let res = offices("Name\n0123\n").unwrap();
+		assert_eq!(res.holidays, None);
+		let problem = offices("Feiertage: Atlantis\n\nName\n0123")
+			.unwrap_err();
+		assert_eq!(problem.line, 1);
+		assert_eq!(problem.kind, ProblemKind::InvalidHolidayCalendar);
+	}
+
+	#[test]
+	fn test_comments_and_metadata() {
+		let input = "Name\n0123\n# Erstmal per Mail anschreiben\n\
+			Email: praxis@example.org\nWeb: https://example.org\n\
+			Adresse: Rheinstraße 1, 64283 Darmstadt\n\
+			Ort: 49.8728, 8.6512\n\
+			Notiz: Rückruf abwarten\nFax: 0124";
+		let res = offices(input).unwrap();
+		let office = &res.data[0];
+		assert_eq!(office.comments.data.len(), 1);
+		assert_eq!(
+			office.comments.data[0].data,
+			"Erstmal per Mail anschreiben"
+		);
+		assert_eq!(office.emails.len(), 1);
+		assert_eq!(office.websites.len(), 1);
+		assert_eq!(office.addresses.len(), 1);
+		assert!(office.location.is_some());
+		assert_eq!(office.notes.len(), 1);
+		let lints = lint(&res);
+		assert_eq!(lints.len(), 1);
+		assert_eq!(lints[0].to_string(), "Name: unknown key \"Fax\"");
+		let input = "Name\n0123\n\
+			Adresse: Rheinstraße 1, 64283 Darmstadt";
+		let lints = lint(&offices(input).unwrap());
+		assert_eq!(lints[0].to_string(), "Name: address without Ort:");
+	}
+
+	#[test]
+	fn test_tags() {
+		let input = "Name\n0123\nTags: VT, Kinder\n\
+			Tags: vt, Kassensitz";
+		let res = offices(input).unwrap();
+		let tags = &res.data[0].tags;
+		assert_eq!(tags.to_string(), "Kassensitz, Kinder, VT");
+		assert!(tags.contains("kinder"));
+		assert!(offices("Name\n0123\nTags: ").is_err());
+		let input = "Name\n0123\nTags: VT,Kinder ,Kassensitz";
+		let res = offices(input).unwrap();
+		let tags = &res.data[0].tags;
+		assert_eq!(tags.to_string(), "Kassensitz, Kinder, VT");
+	}
+
+	#[test]
+	fn test_insurances() {
+		let input = "Name\n0123\nKasse: GKV,PKV\nKasse: pkv , \
+			Kostenerstattung";
+		let res = offices(input).unwrap();
+		let expected = [
+			Insurance::GKV,
+			Insurance::PKV,
+			Insurance::Kostenerstattung,
+		];
+		assert_eq!(res.data[0].insurances, expected);
+		assert!(offices("Name\n0123\nKasse: GKV,AOK").is_err());
+	}
+
+	#[test]
+	fn test_invalid_metadata() {
+		let input = "Name\n0123\nEmail: praxis.example.org";
+		assert!(matches!(office(input), Err(nom::Err::Failure(_))));
+		let input = "Name\n0123\nAdresse: Darmstadt";
+		assert!(matches!(office(input), Err(nom::Err::Failure(_))));
+		let input = "Name\n0123\nOrt: 49.8728";
+		assert!(matches!(office(input), Err(nom::Err::Failure(_))));
+	}
+
+	#[test]
+	fn test_no_catch_all_comments() {
+		assert!(offices("Name\n0123\nirgendwas").is_err());
+	}
+
+	#[test]
+	fn test_normalize() {
+		let input = "\u{feff}A \r\n01\t\r\n  \r\nB\r0\r";
+		assert_eq!(normalize(input), "A\n01\n\nB\n0\n");
+	}
+
+	/// Parses the input and returns the names of all offices, panicking
+	/// when there are any problems.
+	fn office_names(input: &str) -> Vec<String> {
+		offices(input)
+			.unwrap()
+			.data
+			.iter()
+			.map(|x| x.names.to_string())
+			.collect()
+	}
+
+	#[test]
+	fn test_whitespace_variants() {
+		let plain = "A\n06151 111\nMo: 9:00 – 10:00\n\nB\n06151 222\n";
+		let expected = vec!["A", "B"];
+		assert_eq!(office_names(plain), expected);
+		let crlf = plain.replace('\n', "\r\n");
+		assert_eq!(office_names(&crlf), expected);
+		let bom = format!("\u{feff}{}", plain);
+		assert_eq!(office_names(&bom), expected);
+		let trailing = plain.replace('\n', " \t\n");
+		assert_eq!(office_names(&trailing), expected);
+		let blank_lines = plain.replace("\n\n", "\n\n\n\n");
+		assert_eq!(office_names(&blank_lines), expected);
+		let around = format!("\n\n{}\n\n\n", plain);
+		assert_eq!(office_names(&around), expected);
+		let calendar = format!("Feiertage: HE\n\n\n{}", plain);
+		assert_eq!(office_names(&calendar), expected);
+	}
+
+	#[test]
+	fn test_recovery() {
+		let input = "A\n06151 111\nMo: 9:00 – 10:00\n\n\
+			B\n06151 222\nDi: 9:00 – 25:00\n# Kommentar\n\n\
+			C\n06151 333\nMi: 9:00 – 10:00 und so\n\n\
+			D\n06151 444\n# Kommentar\n";
+		let (res, problems) = offices_recovering(input);
+		let names: Vec<_> = res.data.iter()
+			.map(|x| x.names.to_string())
+			.collect();
+		assert_eq!(names, vec!["A", "D"]);
+		assert_eq!(problems.len(), 2);
+		assert_eq!(problems[0].line, 7);
+		assert_eq!(problems[0].kind, ProblemKind::InvalidOffice);
+		assert_eq!(problems[0].text, "Di: 9:00 – 25:00");
+		assert_eq!(problems[1].line, 12);
+		assert_eq!(problems[1].kind, ProblemKind::UnexpectedInput);
+		assert!(offices(input).is_err());
 	}
 }
@@ -1,11 +1,34 @@
+pub mod calls;
+pub mod contact;
+pub mod export;
+pub mod geo;
+pub mod holidays;
 pub mod hrdb;
+pub mod name;
+pub mod phone;
+pub mod practice;
+pub mod profile;
+pub mod query;
+pub mod search;
 pub mod time;
 
-use std::str::FromStr;
-
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
+use std::str::FromStr;
 
-use crate::time::{OfficeHour, OfficeHours, Time};
+use crate::calls::{CallLog, Outcome};
+use crate::contact::{Address, Email, Website};
+use crate::geo::Coordinates;
+use crate::holidays::{Holiday, State};
+use crate::name::{Name, Names};
+use crate::phone::{Phone, PhoneLabel};
+use crate::practice::Practices;
+use crate::profile::Insurance;
+use crate::query::Query;
+use crate::time::{
+	Absence, Absences, Channel, ExtraHour, ExtraHours, OfficeHour, Ranges,
+	Schedules, Time,
+};
 
 fn display_simple_list<T: IntoIterator>(
 	lst: T,
@@ -30,77 +53,31 @@ where
 	}
 }
 
-/// The name of a person.
 #[derive(Debug, Clone)]
-struct Name {
-	data: String,
-}
-
-impl<T: AsRef<str>> From<T> for Name {
-	fn from(str: T) -> Name {
-		let data = String::from(str.as_ref());
-		Name { data }
-	}
+struct Phones {
+	data: Vec<Phone>,
 }
 
-impl fmt::Display for Name {
-	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-		write!(fmt, "{}", self.data)
+impl Phones {
+	/// The phone with the same number as the given one, if any.
+	fn find(&self, phone: &Phone) -> Option<&Phone> {
+		self.data.iter().find(|x| x.same_number(phone))
 	}
-}
-
-/// A set of `Name`s.
-#[derive(Debug, Clone)]
-struct Names {
-	data: Vec<Name>,
-}
 
-impl From<Vec<Name>> for Names {
-	fn from(data: Vec<Name>) -> Names {
-		Names { data }
+	/// Leave out the phones with any of the given labels.
+	fn hide(&mut self, labels: &[PhoneLabel]) {
+		self.data.retain(|x| !labels.iter().any(|l| x.has_label(l)));
 	}
-}
 
-impl fmt::Display for Names {
-	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-		display_simple_list(&self.data, fmt)
+	/// Sort the phones so that those with labels listed first come first.
+	fn prefer(&mut self, labels: &[PhoneLabel]) {
+		self.data.sort_by_key(|x| {
+			labels.iter().position(|l| x.has_label(l))
+				.unwrap_or(labels.len())
+		});
 	}
 }
 
-#[derive(Debug, Clone)]
-struct Phone {
-	data: String,
-}
-
-#[derive(Debug)]
-enum PhoneErr {
-	InvalidChar,
-}
-
-impl FromStr for Phone {
-	type Err = PhoneErr;
-
-	fn from_str(src: &str) -> Result<Phone, Self::Err> {
-		if src.chars().all(|c| c.is_digit(10)) {
-			let data = String::from(src);
-			Ok(Phone { data })
-		} else {
-			Err(PhoneErr::InvalidChar)
-		}
-	}
-}
-
-impl fmt::Display for Phone {
-	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-		write!(fmt, "{}", self.data)
-	}
-}
-
-#[derive(Debug, Clone)]
-struct Phones {
-	data: Vec<Phone>,
-}
-
 impl From<Vec<Phone>> for Phones {
 	fn from(data: Vec<Phone>) -> Phones {
 		Phones { data }
@@ -147,67 +124,613 @@ impl Comments {
 	}
 }
 
+/// A note of our own about an office, as opposed to a `Comment`, which
+/// states what the office says about itself.
+#[derive(Debug, Clone)]
+struct Note {
+	data: String,
+}
+
+impl<T: AsRef<str>> From<T> for Note {
+	fn from(src: T) -> Note {
+		let data = String::from(src.as_ref());
+		Note { data }
+	}
+}
+
+/// Free-form tags of an office like `"VT"` or `"Kinder"`, or a similar set
+/// of short texts like the languages spoken.
+///
+/// Tags are compared ignoring case, so each tag is kept only as it was
+/// first written.
+#[derive(Debug, Clone, Default)]
+struct Tags {
+	data: BTreeSet<String>,
+}
+
+impl Tags {
+	fn contains(&self, tag: &str) -> bool {
+		let tag = tag.to_lowercase();
+		self.data.iter().any(|x| x.to_lowercase() == tag)
+	}
+
+	fn insert(&mut self, tag: &str) {
+		if !self.contains(tag) {
+			self.data.insert(String::from(tag));
+		}
+	}
+}
+
+impl fmt::Display for Tags {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		display_simple_list(&self.data, fmt)
+	}
+}
+
+/// A piece of information of a kind that is not known to this crate,
+/// kept around so that it does not get lost.
+#[derive(Debug, Clone)]
+struct Metadata {
+	key: String,
+	value: String,
+}
+
+impl Metadata {
+	fn new(key: &str, value: &str) -> Metadata {
+		let key = String::from(key);
+		let value = String::from(value);
+		Metadata { key, value }
+	}
+}
+
+impl fmt::Display for Metadata {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}: {}", self.key, self.value)
+	}
+}
+
 #[derive(Debug, Clone)]
 pub struct Office {
+	/// The id given by an `Id:` line, if any, see `id`.
+	id: Option<String>,
 	names: Names,
 	phones: Phones,
-	times: OfficeHours,
+	schedules: Schedules,
+	extra_hours: ExtraHours,
+	absences: Absences,
+	emails: Vec<Email>,
+	websites: Vec<Website>,
+	addresses: Vec<Address>,
+	/// Where the office is, if given explicitly rather than by an address.
+	location: Option<Coordinates>,
+	/// The ways of paying for therapy the office accepts.
+	insurances: Vec<Insurance>,
+	/// The therapy methods offered, e.g. `"VT"`.
+	methods: Tags,
+	/// The age groups treated, e.g. `"Kinder"`.
+	age_groups: Tags,
+	/// The languages therapy is offered in, e.g. `"Englisch"`.
+	languages: Tags,
 	comments: Comments,
+	notes: Vec<Note>,
+	tags: Tags,
+	unknown: Vec<Metadata>,
+	/// The outcome of the last call to the office, if known.
+	status: Option<Outcome>,
 }
 
 impl Office {
 	fn new(names: Names, phones: Phones) -> Office {
-		let times = OfficeHours::empty();
+		let schedules = Schedules::new();
+		let extra_hours = ExtraHours::empty();
+		let absences = Absences::empty();
 		let comments = Comments::empty();
 		Office {
+			id: None,
 			names,
 			phones,
-			times,
+			schedules,
+			extra_hours,
+			absences,
+			emails: Vec::new(),
+			websites: Vec::new(),
+			addresses: Vec::new(),
+			location: None,
+			insurances: Vec::new(),
+			methods: Tags::default(),
+			age_groups: Tags::default(),
+			languages: Tags::default(),
 			comments,
+			notes: Vec::new(),
+			tags: Tags::default(),
+			unknown: Vec::new(),
+			status: None,
 		}
 	}
 
+	/// The id by which the office is known in the call log.
+	///
+	/// This is the slug of its names (see `calls::slug`), unless it has
+	/// been given explicitly by an `Id:` line, e.g. to keep the log when a
+	/// therapist joins or leaves the office. Calls logged under an id no
+	/// office has anymore are reported by `CallLog::unknown_offices`.
+	pub fn id(&self) -> String {
+		let slug = || calls::slug(&self.names.to_string());
+		self.id.clone().unwrap_or_else(slug)
+	}
+
+	fn set_id(&mut self, id: &str) {
+		self.id = Some(String::from(id));
+	}
+
+	fn start_schedule(
+		&mut self,
+		from: Option<::time::Date>,
+		until: Option<::time::Date>,
+	) {
+		self.schedules.start(from, until);
+	}
+
 	fn add_times(&mut self, new_times: Vec<OfficeHour>) {
-		self.times.append(new_times);
+		self.schedules.append(new_times);
+	}
+
+	fn add_extra_hours(&mut self, new_times: Vec<ExtraHour>) {
+		self.extra_hours.append(new_times);
+	}
+
+	fn add_absence(&mut self, absence: Absence) {
+		self.absences.push(absence);
 	}
 
 	fn add_comment(&mut self, comment: Comment) {
 		self.comments.push(comment);
 	}
 
-	fn reachable(&self, time: &Time) -> bool {
-		self.times.contain(time)
+	fn add_email(&mut self, email: Email) {
+		self.emails.push(email);
+	}
+
+	fn add_website(&mut self, website: Website) {
+		self.websites.push(website);
+	}
+
+	fn add_address(&mut self, address: Address) {
+		self.addresses.push(address);
+	}
+
+	fn add_note(&mut self, note: Note) {
+		self.notes.push(note);
+	}
+
+	fn set_location(&mut self, location: Coordinates) {
+		self.location = Some(location);
+	}
+
+	fn add_insurances(&mut self, insurances: Vec<Insurance>) {
+		for insurance in insurances {
+			if !self.insurances.contains(&insurance) {
+				self.insurances.push(insurance);
+			}
+		}
+	}
+
+	fn add_tags(&mut self, tags: Vec<&str>) {
+		Office::insert_all(&mut self.tags, tags);
+	}
+
+	fn add_methods(&mut self, methods: Vec<&str>) {
+		Office::insert_all(&mut self.methods, methods);
+	}
+
+	fn add_age_groups(&mut self, age_groups: Vec<&str>) {
+		Office::insert_all(&mut self.age_groups, age_groups);
+	}
+
+	fn add_languages(&mut self, languages: Vec<&str>) {
+		Office::insert_all(&mut self.languages, languages);
+	}
+
+	fn insert_all(tags: &mut Tags, new_tags: Vec<&str>) {
+		for tag in new_tags.into_iter().filter(|x| !x.is_empty()) {
+			tags.insert(tag);
+		}
+	}
+
+	fn add_unknown(&mut self, metadata: Metadata) {
+		self.unknown.push(metadata);
+	}
+
+	/// Whether the office has the given phone number, which may be given in
+	/// any notation understood by `Phone`, or, unless `exact`, a number
+	/// ending in the same (at least four) digits, e.g. when only the
+	/// subscriber number is known.
+	///
+	/// The digits compared are those of the national significant number,
+	/// i.e. without a trunk prefix or country code, so that e.g. a foreign
+	/// number given in its national format is still found.
+	fn has_phone(&self, number: &str, exact: bool) -> bool {
+		let phone = Phone::from_str(number).ok();
+		let digits: String = match &phone {
+			Some(phone) => String::from(phone.national_number()),
+			None => number.chars().filter(char::is_ascii_digit)
+				.collect(),
+		};
+		self.phones.data.iter().any(|x| {
+			let full = phone.as_ref().is_some_and(|p| {
+				x.same_number(p)
+			});
+			let suffix = digits.len() >= 4 && x.ends_with(&digits);
+			full || (!exact && suffix)
+		})
+	}
+
+	/// Where the office is, as given by its `Ort:` line (see `geo`).
+	fn coordinates(&self) -> Option<Coordinates> {
+		self.location
+	}
+
+	/// Whether the office holds its regular office hours on the given
+	/// date, i.e. it is not absent and, by the given predicate, it is not
+	/// a public holiday.
+	fn open<'a, F>(
+		&'a self,
+		holiday: F,
+	) -> impl Fn(&::time::Date) -> bool + Copy + 'a
+	where
+		F: Fn(&::time::Date) -> bool + Copy + 'a,
+	{
+		move |date| !self.absences.contain(date) && !holiday(date)
+	}
+
+	/// Whether the office can be reached at the given time by the given
+	/// channel, either by its extra hours or by its regular office hours.
+	///
+	/// Regular office hours count only if the office is open (see `open`)
+	/// on the date they started, which for hours crossing midnight is the
+	/// day before.
+	fn reachable<F>(
+		&self,
+		time: &Time,
+		channel: &Channel,
+		holiday: F,
+	) -> bool
+	where
+		F: Fn(&::time::Date) -> bool + Copy,
+	{
+		let open = self.open(holiday);
+		self.extra_hours.contain(time, channel)
+			|| self.schedules.contain(time, channel, open)
+	}
+
+	/// The times at which the office can be reached on the given date by
+	/// the given channel, as by `reachable`.
+	fn hours_on<F>(
+		&self,
+		date: &::time::Date,
+		channel: &Channel,
+		holiday: F,
+	) -> Ranges
+	where
+		F: Fn(&::time::Date) -> bool + Copy,
+	{
+		let mut hours = self.extra_hours.on(date, channel);
+		let open = self.open(holiday);
+		hours.extend(self.schedules.on(date, channel, open));
+		hours
+	}
+
+	/// The office with only those phones and names that are answered at
+	/// the given time by the hours for the given channel.
+	///
+	/// Hours not naming a phone are answered on all of them, hours not
+	/// naming a person by all of the office. Regular office hours count as
+	/// by `reachable`.
+	fn answering<F>(
+		&self,
+		time: &Time,
+		channel: &Channel,
+		holiday: F,
+	) -> Office
+	where
+		F: Fn(&::time::Date) -> bool + Copy,
+	{
+		let (mut phones, mut persons): (Vec<_>, Vec<_>) = self
+			.extra_hours
+			.matching(time, channel)
+			.map(|x| (x.phone(), x.person()))
+			.unzip();
+		let open = self.open(holiday);
+		for x in self.schedules.matching(time, channel, open) {
+			phones.push(x.phone());
+			persons.push(x.person());
+		}
+		let mut office = self.clone();
+		if !phones.contains(&None) {
+			let answered = |x: &Phone| phones.contains(&Some(x));
+			office.phones.data.retain(answered);
+		}
+		if !persons.contains(&None) {
+			let answering = |x: &Name| persons.contains(&Some(x));
+			office.names.retain(answering);
+		}
+		office
 	}
 }
 
-impl fmt::Display for Office {
-	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-		write!(fmt, "{}\n{}\n", self.names, self.phones)?;
+impl Office {
+	/// Write everything but the names and phones of the office.
+	fn fmt_details(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		if let Some(id) = &self.id {
+			writeln!(fmt, "Id: {}", id)?;
+		}
+		for e in &self.emails {
+			writeln!(fmt, "Email: {}", e)?;
+		}
+		for w in &self.websites {
+			writeln!(fmt, "Web: {}", w)?;
+		}
+		for a in &self.addresses {
+			writeln!(fmt, "Adresse: {}", a)?;
+		}
+		if let Some(location) = &self.location {
+			writeln!(fmt, "Ort: {}", location)?;
+		}
+		if !self.insurances.is_empty() {
+			write!(fmt, "Kasse: ")?;
+			display_simple_list(&self.insurances, fmt)?;
+			writeln!(fmt)?;
+		}
+		let sets = [
+			("Verfahren", &self.methods),
+			("Alter", &self.age_groups),
+			("Sprachen", &self.languages),
+		];
+		for (key, set) in sets {
+			if !set.data.is_empty() {
+				writeln!(fmt, "{}: {}", key, set)?;
+			}
+		}
 		for c in &self.comments.data {
-			write!(fmt, "{}\n", c.data)?;
+			writeln!(fmt, "{}", c.data)?;
+		}
+		for n in &self.notes {
+			writeln!(fmt, "Notiz: {}", n.data)?;
+		}
+		if !self.tags.data.is_empty() {
+			writeln!(fmt, "Tags: {}", self.tags)?;
+		}
+		for m in &self.unknown {
+			writeln!(fmt, "{}", m)?;
 		}
 		Ok(())
 	}
 }
 
+impl fmt::Display for Office {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}\n{}\n", self.names, self.phones)?;
+		self.fmt_details(fmt)
+	}
+}
+
+#[derive(Debug, Clone)]
 pub struct Offices {
 	data: Vec<Office>,
+	/// The state whose public holidays the offices observe, if any.
+	holidays: Option<State>,
 }
 
 impl Offices {
-	pub fn filter_time(&self, time: &Time) -> Offices {
+	fn new(data: Vec<Office>, holidays: Option<State>) -> Offices {
+		Offices { data, holidays }
+	}
+
+	/// The public holiday on the given date, if the offices observe one.
+	pub fn holiday(&self, date: &::time::Date) -> Option<Holiday> {
+		self.holidays.and_then(|state| holidays::holiday(date, state))
+	}
+
+	/// The offices reachable at the given time by the given channel, with
+	/// only the phones answered at that time.
+	///
+	/// On public holidays, only extra hours are taken into account. For
+	/// office hours crossing midnight, it is the date they started that
+	/// matters.
+	pub fn filter_time(&self, time: &Time, channel: &Channel) -> Offices {
+		let holiday = |x: &::time::Date| self.holiday(x).is_some();
+		let data = self.data.iter()
+			.filter(|x| x.reachable(time, channel, holiday))
+			.map(|x| x.answering(time, channel, holiday))
+			.collect();
+		Offices::new(data, self.holidays)
+	}
+
+	/// The offices with the given phone number, e.g. to find out who is
+	/// calling.
+	///
+	/// The number may be given in any notation understood by `Phone`. When
+	/// no office has the full number, e.g. because only the subscriber
+	/// number without area code is known, offices with a number ending in
+	/// the same (at least four) digits are returned (see
+	/// `Office::has_phone`).
+	pub fn find_by_phone(&self, number: &str) -> Offices {
+		let with_phone = |exact| -> Vec<Office> {
+			self.data.iter()
+				.filter(|x| x.has_phone(number, exact))
+				.cloned()
+				.collect()
+		};
+		let mut data = with_phone(true);
+		if data.is_empty() {
+			data = with_phone(false);
+		}
+		Offices::new(data, self.holidays)
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.data.is_empty()
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &Office> {
+		self.data.iter()
+	}
+
+	/// The office with the given id (see `Office::id`), if any.
+	pub fn by_id(&self, id: &str) -> Option<&Office> {
+		self.data.iter().find(|x| x.id() == id)
+	}
+
+	/// The offices with their status taken from the call log, i.e. the
+	/// outcome of the last call to each of them.
+	pub fn with_status(&self, log: &CallLog) -> Offices {
+		let mut data = self.data.clone();
+		for office in &mut data {
+			let last = log.last(&office.id());
+			office.status = last.map(|x| x.outcome());
+		}
+		Offices::new(data, self.holidays)
+	}
+
+	/// The offices sorted by the family name of their first person (see
+	/// `Name::sort_key`), instead of in the order of the database.
+	pub fn sorted_by_name(&self) -> Offices {
+		let mut data = self.data.clone();
+		data.sort_by_cached_key(|x| x.names.sort_key());
+		Offices::new(data, self.holidays)
+	}
+
+	/// The offices matching the search query, best matches first.
+	///
+	/// All words of the query need to be found in the names, comments,
+	/// notes, tags or metadata of an office, see `search` for the details.
+	pub fn search(&self, query: &str) -> Offices {
+		let mut found: Vec<(u32, Office)> = self
+			.sorted_by_name()
+			.data
+			.into_iter()
+			.filter_map(|x| Some((search::score(&x, query)?, x)))
+			.collect();
+		found.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+		let data = found.into_iter().map(|(_, x)| x).collect();
+		Offices::new(data, self.holidays)
+	}
+
+	/// The offices matching the query run at the given date, see `query`
+	/// for its syntax.
+	pub fn filter(&self, query: &Query, today: &::time::Date) -> Offices {
+		let data = self.data.iter()
+			.filter(|x| query.matches(x, today))
+			.cloned()
+			.collect();
+		Offices::new(data, self.holidays)
+	}
+
+	/// The offices with all of the given tags and none of the excluded
+	/// ones.
+	pub fn filter_tags(&self, tags: &[&str], excluded: &[&str]) -> Offices {
 		let data = self.data.iter()
-			.filter(|x| x.reachable(time))
+			.filter(|x| tags.iter().all(|tag| x.tags.contains(tag)))
+			.filter(|x| !excluded.iter().any(|tag| {
+				x.tags.contains(tag)
+			}))
 			.cloned()
 			.collect();
-		Offices { data }
+		Offices::new(data, self.holidays)
+	}
+
+	/// All tags of the offices with the number of offices having them.
+	///
+	/// Like the tags of a single office, they are compared ignoring case,
+	/// so a tag written differently by several offices is counted once.
+	pub fn tags(&self) -> BTreeMap<String, usize> {
+		let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+		for tag in self.data.iter().flat_map(|x| &x.tags.data) {
+			let lowercase = tag.to_lowercase();
+			let written = counts
+				.keys()
+				.find(|x| x.to_lowercase() == lowercase)
+				.cloned()
+				.unwrap_or_else(|| tag.clone());
+			*counts.entry(written).or_insert(0) += 1;
+		}
+		counts
+	}
+
+	/// The offices sorted by their distance from the given point, nearest
+	/// first, leaving out those farther away than the maximal distance in
+	/// kilometres, if any.
+	///
+	/// Offices whose location is unknown come last, they are kept even
+	/// given a maximal distance.
+	pub fn near(&self, origin: &Coordinates, max: Option<f64>) -> Offices {
+		let distance = |x: &Office| {
+			x.coordinates().map(|x| x.distance(origin))
+		};
+		let mut data: Vec<(Option<f64>, Office)> = self.data.iter()
+			.map(|x| (distance(x), x.clone()))
+			.filter(|(km, _)| match (km, max) {
+				(Some(km), Some(max)) => *km <= max,
+				_ => true,
+			})
+			.collect();
+		data.sort_by(|(a, _), (b, _)| match (a, b) {
+			(Some(a), Some(b)) => a.total_cmp(b),
+			(a, b) => b.is_some().cmp(&a.is_some()),
+		});
+		let data = data.into_iter().map(|(_, x)| x).collect();
+		Offices::new(data, self.holidays)
+	}
+
+	/// The offices grouped into practices by the phones they share.
+	pub fn practices(&self) -> Practices {
+		Practices::group(&self.data)
+	}
+
+	/// The offices grouped into practices as by `practices`, together with
+	/// the hours of each practice on the given date by the given channel.
+	pub fn practices_on(
+		&self,
+		date: &::time::Date,
+		channel: &Channel,
+	) -> Practices {
+		let holiday = |x: &::time::Date| self.holiday(x).is_some();
+		self.practices()
+			.with_hours(|x| x.hours_on(date, channel, holiday))
+	}
+
+	/// The offices without the phones with any of the given labels.
+	///
+	/// Offices left without any phone by this are left out entirely, e.g.
+	/// when only their answering machine could be called right now.
+	pub fn hide_phones(&self, labels: &[PhoneLabel]) -> Offices {
+		let data = self.data.iter()
+			.filter_map(|x| {
+				let mut office = x.clone();
+				office.phones.hide(labels);
+				let emptied = office.phones.data.is_empty()
+					&& !x.phones.data.is_empty();
+				(!emptied).then_some(office)
+			})
+			.collect();
+		Offices::new(data, self.holidays)
+	}
+
+	/// The offices with the phones with any of the given labels listed
+	/// first, in the order of the labels.
+	pub fn prefer_phones(&self, labels: &[PhoneLabel]) -> Offices {
+		let mut data = self.data.clone();
+		for office in &mut data {
+			office.phones.prefer(labels);
+		}
+		Offices::new(data, self.holidays)
 	}
 }
 
 impl fmt::Display for Offices {
 	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
 		for o in &self.data {
-			write!(fmt, "{}\n", o)?;
+			writeln!(fmt, "{}", o)?;
 		}
 		Ok(())
 	}
@@ -215,6 +738,6 @@ impl fmt::Display for Offices {
 
 impl From<Vec<Office>> for Offices {
 	fn from(data: Vec<Office>) -> Self {
-		Offices { data }
+		Offices::new(data, None)
 	}
 }
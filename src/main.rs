@@ -1,17 +1,311 @@
 extern crate sprechzeiten;
 
-use std::fs::File;
+use std::env;
+use std::fs::{File, OpenOptions};
 use std::io::prelude::*;
+use std::io::ErrorKind;
+use std::process;
+use std::str::FromStr;
 
-use crate::sprechzeiten::{hrdb, time};
+use crate::sprechzeiten::calls::{Call, CallLog, Outcome};
+use crate::sprechzeiten::geo::Coordinates;
+use crate::sprechzeiten::phone::PhoneLabel;
+use crate::sprechzeiten::profile::{self, Profile, Ranking};
+use crate::sprechzeiten::query::Query;
+use crate::sprechzeiten::{export, hrdb, time, Office, Offices};
 
-fn main() {
+/// The file calls are logged to, see `calls`.
+const CALL_LOG: &str = "data/anrufe.log";
+
+/// Read the call log, reporting problems with it on stderr: lines that
+/// cannot be parsed, which are skipped, and calls to offices that are not
+/// in the database (anymore).
+///
+/// A missing log is taken to be empty, as before the first call.
+fn load_calls(offices: &Offices) -> CallLog {
+	let contents = match std::fs::read_to_string(CALL_LOG) {
+		Ok(contents) => contents,
+		Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+		Err(e) => fail(&format!("Unable to read {}: {}", CALL_LOG, e)),
+	};
+	let (calls, problems) = CallLog::recovering(&contents);
+	for problem in problems {
+		eprintln!("{}: {}", CALL_LOG, problem);
+	}
+	for id in calls.unknown_offices(offices) {
+		eprintln!(
+			"warning: {}: calls to unknown office \"{}\"",
+			CALL_LOG, id,
+		);
+	}
+	calls
+}
+
+/// Read the database, reporting all problems with it on stderr.
+fn load() -> Offices {
 	let mut f = File::open("data/therapeuten.hrdb").expect("Not found.");
 	let mut contents = String::new();
 	f.read_to_string(&mut contents).expect("Misread.");
-	let (_, offices) = hrdb::offices(contents.as_ref())
-		.expect("Parsing unsuccessful.");
+	let (offices, problems) = hrdb::offices_recovering(contents.as_ref());
+	for problem in problems {
+		eprintln!("{}", problem);
+	}
+	for lint in hrdb::lint(&offices) {
+		eprintln!("warning: {}", lint);
+	}
+	offices
+}
+
+/// Print an error message and exit.
+fn fail(message: &str) -> ! {
+	eprintln!("{}", message);
+	process::exit(1);
+}
+
+/// The value given to an option like `--channel Video`, if any.
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+	let position = args.iter().position(|arg| arg == name)?;
+	match args.get(position + 1) {
+		Some(value) => Some(value),
+		None => fail(&format!("Missing value for {}", name)),
+	}
+}
+
+/// The comma separated values given to an option like `--tag VT,Kinder`.
+fn values<'a>(args: &'a [String], name: &str) -> Vec<&'a str> {
+	let values = option(args, name).unwrap_or_default();
+	values.split(',').filter(|x| !x.is_empty()).collect()
+}
+
+/// The comma separated phone labels given to an option like
+/// `--hide-phone AB,Sekretariat`.
+fn phone_labels(args: &[String], name: &str) -> Vec<PhoneLabel> {
+	values(args, name)
+		.into_iter()
+		// This cannot panic! since any label is accepted
+		.map(|x| x.parse().unwrap())
+		.collect()
+}
+
+/// The arguments before the first option, e.g. the words of a search.
+fn operands(args: &[String]) -> &[String] {
+	let end = args.iter().position(|x| x.starts_with("--"));
+	&args[..end.unwrap_or(args.len())]
+}
+
+/// The offices selected by the options common to all listings: those
+/// with all tags given to `--tag` and none given to `--without-tag` and
+/// matching the query given to `--where`, if any, e.g.
+/// `--where "day=Di and from>=9:00"`.
+fn selected(offices: &Offices, args: &[String]) -> Offices {
+	let tagged = offices.filter_tags(
+		&values(args, "--tag"),
+		&values(args, "--without-tag"),
+	);
+	let now = time::Time::now().expect("Unable to get current local time");
+	match option(args, "--where") {
+		Some(query) => match Query::from_str(query) {
+			Ok(query) => tagged.filter(&query, now.date()),
+			Err(e) => fail(&format!("Invalid query: {}", e)),
+		},
+		None => tagged,
+	}
+}
+
+/// The offices sorted by their distance from the point given to `--near`,
+/// e.g. `--near 49.8728,8.6512`, and without those farther away than the
+/// kilometres given to `--within`.
+fn nearby(offices: Offices, args: &[String]) -> Offices {
+	let origin = match option(args, "--near") {
+		Some(origin) => Coordinates::from_str(origin)
+			.unwrap_or_else(|_| fail("Usage: --near <lat>,<lon>")),
+		None if option(args, "--within").is_some() => {
+			fail("--within needs --near")
+		}
+		None => return offices,
+	};
+	let max = option(args, "--within").map(|km| {
+		profile::distance(km).unwrap_or_else(|_| {
+			fail("Expected kilometres like --within 7,5")
+		})
+	});
+	offices.near(&origin, max)
+}
+
+/// The profile read from the file given to `--profile`, if any.
+fn profile(args: &[String]) -> Option<Profile> {
+	let path = option(args, "--profile")?;
+	let contents = std::fs::read_to_string(path).unwrap_or_else(|e| {
+		fail(&format!("Unable to read {}: {}", path, e))
+	});
+	match Profile::from_str(&contents) {
+		Ok(profile) => Some(profile),
+		Err(e) => fail(&format!("Invalid profile {}: {}", path, e)),
+	}
+}
+
+/// Print the offices that can be reached right now, grouped into practices
+/// so that a shared phone shows up only once, with their hours today.
+///
+/// By default these are the offices that can be called, other channels can
+/// be chosen by `--channel`. Phones with some labels can be left out by
+/// `--hide-phone` or listed first by `--prefer-phone`, and the offices can
+/// be narrowed down as for all listings (see `selected`). They are sorted by
+/// name, or by distance (see `nearby`). Given a `--profile`, the practices
+/// are then ranked by how well their best fitting office fits it.
+fn now(offices: &Offices, args: &[String]) {
+	let channel = match option(args, "--channel") {
+		Some(name) => time::Channel::from_str(name).unwrap_or_else(|_| {
+			fail("Channels: Telefon, Sprechstunde, Video")
+		}),
+		None => time::Channel::Phone,
+	};
 	let now = time::Time::now().expect("Unable to get current local time");
-	let current_offices = offices.filter_time(&now);
-	println!("{}", current_offices);
+	if let Some(holiday) = offices.holiday(now.date()) {
+		println!("Today is a public holiday: {}", holiday);
+	}
+	let current_offices = selected(offices, args)
+		.filter_time(&now, &channel)
+		.hide_phones(&phone_labels(args, "--hide-phone"))
+		.prefer_phones(&phone_labels(args, "--prefer-phone"))
+		.sorted_by_name();
+	let practices = nearby(current_offices, args)
+		.practices_on(now.date(), &channel);
+	match profile(args) {
+		Some(profile) => println!("{}", practices.ranked(&profile)),
+		None => println!("{}", practices),
+	}
+}
+
+/// Print the selected offices (see `selected`) sorted as by `now`.
+fn list(offices: &Offices, args: &[String]) {
+	let offices = nearby(selected(offices, args).sorted_by_name(), args);
+	match profile(args) {
+		Some(profile) => print!("{}", Ranking::new(&offices, &profile)),
+		None => print!("{}", offices),
+	}
+}
+
+/// Print the selected offices (see `selected`) as vCards.
+fn export(offices: &Offices, args: &[String]) {
+	let offices = selected(offices, args);
+	print!("{}", export::VCards::new(&offices));
+}
+
+/// Print all tags with the number of offices having them.
+fn tags(offices: &Offices) {
+	for (tag, count) in offices.tags() {
+		println!("{}: {}", tag, count);
+	}
+}
+
+/// Print the offices with the phone number given as the arguments, e.g. to
+/// find out who called.
+fn who(offices: &Offices, args: &[String]) {
+	let number = operands(args);
+	if number.is_empty() {
+		fail("Usage: who <number>");
+	}
+	let found = selected(offices, args).find_by_phone(&number.join(" "));
+	if found.is_empty() {
+		fail("No office with this number");
+	}
+	print!("{}", found);
+}
+
+/// Print the offices matching the search query given as the arguments.
+fn search(offices: &Offices, args: &[String]) {
+	let query = operands(args);
+	if query.is_empty() {
+		fail("Usage: search <query>");
+	}
+	let found = selected(offices, args).search(&query.join(" "));
+	if found.is_empty() {
+		fail("No office found");
+	}
+	print!("{}", found);
+}
+
+/// The office given on the command line, either by its id or by a search
+/// finding only it.
+fn office(offices: &Offices, query: &str) -> Office {
+	if let Some(office) = offices.by_id(query) {
+		return office.clone();
+	}
+	let found = offices.search(query);
+	let ids: Vec<String> = found.iter().map(Office::id).collect();
+	let first = found.iter().next().cloned();
+	match first {
+		Some(office) if ids.len() == 1 => office,
+		Some(_) => fail(&format!("Which office? {}", ids.join(", "))),
+		None => fail("No office found"),
+	}
+}
+
+/// Log a call to an office given by the arguments, e.g.
+/// `log hegel besetzt` or `log allissat Absage 06151 61544`.
+fn log(offices: &Offices, args: &[String]) {
+	let [query, outcome, number @ ..] = args else {
+		fail("Usage: log <office> <outcome> [number]");
+	};
+	let office = office(offices, query);
+	let outcome = Outcome::from_str(outcome).unwrap_or_else(|_| {
+		fail("Outcomes: erreicht, besetzt, AB, keine-Antwort, \
+			Rückruf, Warteliste, Absage")
+	});
+	let number = match number {
+		[] => None,
+		number => Some(number.join(" ").parse().unwrap_or_else(|_| {
+			fail("Invalid number")
+		})),
+	};
+	let call = Call::now(&office.id(), number, outcome)
+		.expect("Unable to get current local time");
+	let mut file = OpenOptions::new()
+		.create(true)
+		.append(true)
+		.open(CALL_LOG)
+		.unwrap_or_else(|e| {
+			fail(&format!("Unable to open {}: {}", CALL_LOG, e))
+		});
+	writeln!(file, "{}", call.line()).expect("Unable to write call log");
+	println!("{}: {}", office.id(), call);
+}
+
+/// Print the calls to the office given by the arguments, or to all offices.
+fn history(offices: &Offices, calls: &CallLog, args: &[String]) {
+	let selected = match args {
+		[] => offices.clone(),
+		args => Offices::from(vec![office(offices, &args.join(" "))]),
+	};
+	for office in selected.iter() {
+		let calls = calls.of(&office.id());
+		if !calls.is_empty() || !args.is_empty() {
+			println!("{}\n{}", office.id(), calls);
+		}
+	}
+}
+
+fn main() {
+	let args: Vec<String> = env::args().skip(1).collect();
+	let offices = load();
+	let calls = load_calls(&offices);
+	let offices = offices.with_status(&calls);
+	match args.first().map(String::as_str) {
+		None => now(&offices, &args),
+		Some("now") => now(&offices, &args[1..]),
+		Some("list") => list(&offices, &args[1..]),
+		Some("export") => export(&offices, &args[1..]),
+		Some("who") => who(&offices, &args[1..]),
+		Some("search") => search(&offices, &args[1..]),
+		Some("tags") => tags(&offices),
+		Some("log") => log(&offices, &args[1..]),
+		Some("history") => history(&offices, &calls, &args[1..]),
+		Some(command) => fail(&format!(
+			"Unknown command: {}\n\
+			Commands: now, list, export, who, search, tags, log, \
+			history",
+			command,
+		)),
+	}
 }
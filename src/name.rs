@@ -0,0 +1,182 @@
+//! Names of people, split into titles, given names and family name.
+
+use std::fmt;
+
+use crate::display_simple_list;
+
+/// The key by which German names are sorted according to DIN 5007-1, i.e.
+/// ignoring case and diacritics and treating `ß` as `ss`.
+pub fn din_5007(src: &str) -> String {
+	let mut key = String::with_capacity(src.len());
+	for c in src.chars().flat_map(char::to_lowercase) {
+		match c {
+			'ä' | 'à' | 'á' | 'â' => key.push('a'),
+			'ç' => key.push('c'),
+			'è' | 'é' | 'ê' | 'ë' => key.push('e'),
+			'ì' | 'í' | 'î' | 'ï' => key.push('i'),
+			'ñ' => key.push('n'),
+			'ö' | 'ò' | 'ó' | 'ô' => key.push('o'),
+			'ü' | 'ù' | 'ú' | 'û' => key.push('u'),
+			'ß' => key.push_str("ss"),
+			_ => key.push(c),
+		}
+	}
+	key
+}
+
+/// The name of a person.
+///
+/// Names are written as in a letter: optionally some titles, which are the
+/// leading words ending in a dot (like `"Dr."` or `"Dipl.-Psych."`), the
+/// given names and the family name. The family name is the last word,
+/// together with any lowercase particles preceding it (like `"von"`), so
+/// double-barrelled names need to be joined by a dash as in
+/// `"Keil-Hujer"`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Name {
+	titles: Vec<String>,
+	given_names: Vec<String>,
+	/// The family name including particles, e.g. `["von", "Stein"]`.
+	family_name: Vec<String>,
+}
+
+impl Name {
+	pub fn titles(&self) -> &[String] {
+		&self.titles
+	}
+
+	pub fn given_names(&self) -> String {
+		self.given_names.join(" ")
+	}
+
+	pub fn family_name(&self) -> String {
+		self.family_name.join(" ")
+	}
+
+	/// The key by which names are sorted: the family name without its
+	/// particles, then the given names (see `din_5007`).
+	pub fn sort_key(&self) -> String {
+		let family: Vec<&str> = self
+			.family_name
+			.iter()
+			.map(String::as_str)
+			.skip_while(|x| x.starts_with(char::is_lowercase))
+			.collect();
+		let family = din_5007(&family.join(" "));
+		format!("{} {}", family, din_5007(&self.given_names()))
+	}
+}
+
+impl<T: AsRef<str>> From<T> for Name {
+	fn from(str: T) -> Name {
+		let words = str.as_ref().split_whitespace();
+		let mut words: Vec<String> = words.map(String::from).collect();
+		let titles_end = words
+			.iter()
+			.take(words.len().saturating_sub(1))
+			.take_while(|x| x.ends_with('.'))
+			.count();
+		let titles: Vec<String> = words.drain(..titles_end).collect();
+		let particle = |x: &String| x.starts_with(char::is_lowercase);
+		let mut family_start = words.len().saturating_sub(1);
+		while family_start > 1 && particle(&words[family_start - 1]) {
+			family_start -= 1;
+		}
+		let family_name = words.split_off(family_start);
+		let given_names = words;
+		Name { titles, given_names, family_name }
+	}
+}
+
+impl fmt::Display for Name {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		let words = self
+			.titles
+			.iter()
+			.chain(&self.given_names)
+			.chain(&self.family_name);
+		let words: Vec<&str> = words.map(String::as_str).collect();
+		write!(fmt, "{}", words.join(" "))
+	}
+}
+
+/// A set of `Name`s.
+#[derive(Debug, Clone)]
+pub struct Names {
+	data: Vec<Name>,
+}
+
+impl Names {
+	/// The first name, e.g. for address books that know only one name
+	/// per entry.
+	pub fn first(&self) -> Option<&Name> {
+		self.data.first()
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &Name> {
+		self.data.iter()
+	}
+
+	/// Keep only the names for which the predicate holds.
+	pub fn retain<F: FnMut(&Name) -> bool>(&mut self, predicate: F) {
+		self.data.retain(predicate)
+	}
+
+	/// The name written as the given one, if any.
+	pub fn find(&self, name: &str) -> Option<&Name> {
+		self.data.iter().find(|x| x.to_string() == name)
+	}
+
+	/// The key by which offices are sorted, that of their first name.
+	pub fn sort_key(&self) -> String {
+		self.first().map(Name::sort_key).unwrap_or_default()
+	}
+}
+
+impl From<Vec<Name>> for Names {
+	fn from(data: Vec<Name>) -> Names {
+		Names { data }
+	}
+}
+
+impl fmt::Display for Names {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		display_simple_list(&self.data, fmt)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parts() {
+		let name = Name::from("Dr. Dipl.-Psych. Ute Sabine Bamberger");
+		assert_eq!(name.titles(), ["Dr.", "Dipl.-Psych."]);
+		assert_eq!(name.given_names(), "Ute Sabine");
+		assert_eq!(name.family_name(), "Bamberger");
+		let expected = "Dr. Dipl.-Psych. Ute Sabine Bamberger";
+		assert_eq!(name.to_string(), expected);
+		let name = Name::from("Michèle  Keil-Hujer");
+		assert_eq!(name.given_names(), "Michèle");
+		assert_eq!(name.family_name(), "Keil-Hujer");
+		assert_eq!(name.to_string(), "Michèle Keil-Hujer");
+		let name = Name::from("Anna von Stein");
+		assert_eq!(name.family_name(), "von Stein");
+		assert_eq!(name.sort_key(), "stein anna");
+		let name = Name::from("Praxis");
+		assert_eq!(name.family_name(), "Praxis");
+		assert!(name.titles().is_empty());
+	}
+
+	#[test]
+	fn sorting() {
+		let names = ["Weber", "Lüder", "Dr. Kai Lucas", "Anna Luß"];
+		let mut names: Vec<Name> = names.map(Name::from).to_vec();
+		names.sort_by_key(Name::sort_key);
+		let names: Vec<String> =
+			names.iter().map(|x| x.to_string()).collect();
+		let expected = ["Dr. Kai Lucas", "Lüder", "Anna Luß", "Weber"];
+		assert_eq!(names, expected);
+	}
+}
@@ -0,0 +1,408 @@
+//! Phone numbers in the notations commonly found on websites and business
+//! cards, normalized to E.164.
+//!
+//! Numbers are accepted with spaces, slashes, dashes and parentheses as
+//! separators, e.g. `"+49 6151 374815"`, `"06151/374815"` or
+//! `"(06151) 37 48 15"`. Numbers without a country code are taken to be
+//! from `DEFAULT_COUNTRY`.
+//!
+//! To separate the area code from the subscriber number for display, the
+//! German area codes are bundled in `vorwahlen.csv`, in the format of the
+//! list of area codes (Vorwahlverzeichnis) published by the
+//! Bundesnetzagentur, so that a new edition of that list can replace it as
+//! is. The codes of the mobile networks, which that list leaves out, follow
+//! from the numbering plan, see `mobile_code_len`. Numbers with unknown area
+//! codes are displayed without the separation.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The country code of Germany, whose area codes are known.
+const GERMANY: u16 = 49;
+
+/// The country numbers without a country code are assumed to be from.
+pub const DEFAULT_COUNTRY: u16 = GERMANY;
+
+/// The list of German area codes of the Bundesnetzagentur, with a header
+/// line and a line `"Ortsnetzkennzahl;Ortsnetzname;KennungAktiv"` per area,
+/// e.g. `"6151;Darmstadt;1"`. The codes are given without the trunk prefix
+/// `0`, areas no longer in use are marked by a `0` in the last column.
+const AREA_CODES: &str = include_str!("vorwahlen.csv");
+
+/// The German area codes in use, see `AREA_CODES`.
+fn area_codes() -> impl Iterator<Item = &'static str> {
+	AREA_CODES.lines().skip(1).filter_map(|line| {
+		let mut fields = line.split(';');
+		let field = |x: &'static str| x.trim().trim_matches('"');
+		let code = field(fields.next()?);
+		let active = fields.nth(1).is_none_or(|x| field(x) != "0");
+		(active && !code.is_empty()).then_some(code)
+	})
+}
+
+/// The length of the code of a German mobile network at the start of a
+/// national number, if it is a mobile number, leaving at least one digit
+/// of subscriber number.
+///
+/// By the numbering plan of the Bundesnetzagentur, mobile numbers start
+/// with `15` and two more digits (e.g. `1556`) or with `16` or `17` and
+/// one more digit (e.g. `176`). Area codes never start with `1`, so these
+/// do not clash with them.
+fn mobile_code_len(number: &str) -> Option<usize> {
+	let len = if number.starts_with("15") {
+		4
+	} else if number.starts_with("16") || number.starts_with("17") {
+		3
+	} else {
+		return None;
+	};
+	(number.len() > len).then_some(len)
+}
+
+/// The length of the longest of the codes at the start of a national
+/// number, if any, leaving at least one digit of subscriber number.
+fn area_code_len<'a, I>(number: &str, codes: I) -> Option<usize>
+where
+	I: IntoIterator<Item = &'a str>,
+{
+	codes
+		.into_iter()
+		.filter(|x| number.len() > x.len() && number.starts_with(x))
+		.map(str::len)
+		.max()
+}
+
+/// The length of the country code at the start of an international number.
+///
+/// Country codes are prefix free: `1` and `7` are the only ones with a
+/// single digit, those starting with one of the two-digit codes listed here
+/// have two digits and all others three.
+fn country_code_len(digits: &str) -> usize {
+	const TWO_DIGITS: [&str; 44] = [
+		"20", "27", "30", "31", "32", "33", "34", "36", "39", "40",
+		"41", "43", "44", "45", "46", "47", "48", "49", "51", "52",
+		"53", "54", "55", "56", "57", "58", "60", "61", "62", "63",
+		"64", "65", "66", "81", "82", "84", "86", "90", "91", "92",
+		"93", "94", "95", "98",
+	];
+	if digits.starts_with('1') || digits.starts_with('7') {
+		1
+	} else if TWO_DIGITS.iter().any(|x| digits.starts_with(x)) {
+		2
+	} else {
+		3
+	}
+}
+
+/// The kind of line a phone number leads to.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PhoneLabel {
+	/// The main line of the office.
+	Praxis,
+	/// A mobile phone.
+	Mobil,
+	/// A line only ever answered by an answering machine.
+	AB,
+	/// A secretary or reception desk.
+	Sekretariat,
+	/// Any other kind, as written in the data.
+	Other(String),
+}
+
+impl FromStr for PhoneLabel {
+	type Err = std::convert::Infallible;
+
+	/// Parses the known labels ignoring case, anything else is kept as is.
+	fn from_str(src: &str) -> Result<PhoneLabel, Self::Err> {
+		let known = [
+			PhoneLabel::Praxis,
+			PhoneLabel::Mobil,
+			PhoneLabel::AB,
+			PhoneLabel::Sekretariat,
+		];
+		let other = || PhoneLabel::Other(String::from(src));
+		let label = known
+			.into_iter()
+			.find(|x| x.to_string().eq_ignore_ascii_case(src))
+			.unwrap_or_else(other);
+		Ok(label)
+	}
+}
+
+impl fmt::Display for PhoneLabel {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			PhoneLabel::Praxis => write!(fmt, "Praxis"),
+			PhoneLabel::Mobil => write!(fmt, "Mobil"),
+			PhoneLabel::AB => write!(fmt, "AB"),
+			PhoneLabel::Sekretariat => write!(fmt, "Sekretariat"),
+			PhoneLabel::Other(label) => write!(fmt, "{}", label),
+		}
+	}
+}
+
+/// A phone number, optionally labelled with the kind of line it leads to.
+///
+/// A phone usually has at most one label, but may have several, e.g. when
+/// offices sharing the number label it differently.
+///
+/// Two phones are equal if both their numbers and their labels are, the
+/// notation they were written in does not matter. To compare only the
+/// numbers, use `same_number`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Phone {
+	country_code: u16,
+	/// The national significant number, i.e. without the trunk prefix.
+	number: String,
+	/// The length of the area code at the start of `number`, if known.
+	area_code_len: Option<usize>,
+	/// The labels, each only once, in the order they were added.
+	labels: Vec<PhoneLabel>,
+}
+
+#[derive(Debug)]
+pub enum PhoneErr {
+	InvalidChar,
+	MissingAreaCode,
+	TooShort,
+	TooLong,
+}
+
+impl Phone {
+	/// Parses a phone number, assuming the given country for numbers
+	/// without a country code.
+	pub fn parse(
+		src: &str,
+		default_country: u16,
+	) -> Result<Phone, PhoneErr> {
+		let separator = |c: char| " /-()".contains(c);
+		let valid_char = |c: char| c.is_ascii_digit() || separator(c);
+		let rest = src.strip_prefix('+').unwrap_or(src);
+		if !rest.chars().all(valid_char) {
+			return Err(PhoneErr::InvalidChar);
+		}
+		let international =
+			src.starts_with('+') || src.starts_with("00");
+		// International numbers often give the trunk prefix as "(0)"
+		let src = if international {
+			src.replacen("(0)", "", 1)
+		} else {
+			String::from(src)
+		};
+		let digits: String =
+			src.chars().filter(|c| c.is_ascii_digit()).collect();
+		let (country_code, number) = if international {
+			let digits =
+				digits.strip_prefix("00").unwrap_or(&digits);
+			let len = country_code_len(digits).min(digits.len());
+			let (code, number) = digits.split_at(len);
+			let code =
+				code.parse().map_err(|_| PhoneErr::TooShort)?;
+			(code, number)
+		} else {
+			let number = digits
+				.strip_prefix('0')
+				.ok_or(PhoneErr::MissingAreaCode)?;
+			(default_country, number)
+		};
+		if number.len() < 3 {
+			return Err(PhoneErr::TooShort);
+		}
+		let code_len = country_code.to_string().len();
+		if code_len + number.len() > 15 {
+			return Err(PhoneErr::TooLong);
+		}
+		let area_code_len = if country_code == GERMANY {
+			area_code_len(number, area_codes())
+				.or_else(|| mobile_code_len(number))
+		} else {
+			None
+		};
+		let number = String::from(number);
+		let labels = Vec::new();
+		Ok(Phone { country_code, number, area_code_len, labels })
+	}
+
+	/// The number in E.164 format, e.g. `"+496151374815"`.
+	pub fn e164(&self) -> String {
+		format!("+{}{}", self.country_code, self.number)
+	}
+
+	/// The number as a `tel:` URI (RFC 3966), e.g. `"tel:+496151374815"`.
+	pub fn tel_uri(&self) -> String {
+		format!("tel:{}", self.e164())
+	}
+
+	/// The national significant number, i.e. the number without country
+	/// code and trunk prefix, e.g. `"6151374815"`.
+	pub fn national_number(&self) -> &str {
+		&self.number
+	}
+
+	/// Whether the national significant number ends with the given digits,
+	/// e.g. when only the subscriber number is known.
+	pub fn ends_with(&self, digits: &str) -> bool {
+		self.number.ends_with(digits)
+	}
+
+	/// Whether the other phone has the same number, ignoring the labels.
+	pub fn same_number(&self, other: &Phone) -> bool {
+		self.country_code == other.country_code
+			&& self.number == other.number
+	}
+
+	pub fn labels(&self) -> &[PhoneLabel] {
+		&self.labels
+	}
+
+	pub fn has_label(&self, label: &PhoneLabel) -> bool {
+		self.labels.contains(label)
+	}
+
+	/// Add the label, unless the phone already has it.
+	pub fn add_label(&mut self, label: PhoneLabel) {
+		if !self.has_label(&label) {
+			self.labels.push(label);
+		}
+	}
+
+	/// The number without its label, grouped according to DIN 5008, e.g.
+	/// `"06151 374815"` or `"+33 142681234"`.
+	///
+	/// Numbers from `DEFAULT_COUNTRY` are written in national format, all
+	/// others in international format.
+	fn din_5008(&self) -> String {
+		let (area_code, subscriber) = match self.area_code_len {
+			Some(len) => self.number.split_at(len),
+			None => ("", self.number.as_str()),
+		};
+		let grouped = if area_code.is_empty() {
+			String::from(subscriber)
+		} else {
+			format!("{} {}", area_code, subscriber)
+		};
+		if self.country_code == DEFAULT_COUNTRY {
+			format!("0{}", grouped)
+		} else {
+			format!("+{} {}", self.country_code, grouped)
+		}
+	}
+}
+
+impl FromStr for Phone {
+	type Err = PhoneErr;
+
+	fn from_str(src: &str) -> Result<Phone, Self::Err> {
+		Phone::parse(src, DEFAULT_COUNTRY)
+	}
+}
+
+impl fmt::Display for Phone {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}", self.din_5008())?;
+		if self.labels.is_empty() {
+			return Ok(());
+		}
+		write!(fmt, " (")?;
+		crate::display_simple_list(&self.labels, fmt)?;
+		write!(fmt, ")")
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn notations() {
+		let notations = [
+			"06151374815",
+			"06151 374815",
+			"06151/374815",
+			"(06151) 37 48 15",
+			"06151-37 48 15",
+			"+49 6151 374815",
+			"+49 (0) 6151 374815",
+			"0049 6151 374815",
+		];
+		for notation in notations {
+			let phone = Phone::from_str(notation).unwrap();
+			assert_eq!(phone.e164(), "+496151374815");
+			assert_eq!(phone.to_string(), "06151 374815");
+		}
+	}
+
+	#[test]
+	fn invalid() {
+		assert!(Phone::from_str("6151374815").is_err());
+		assert!(Phone::from_str("06151 37481x").is_err());
+		assert!(Phone::from_str("+49 6151 +374815").is_err());
+		assert!(Phone::from_str("01").is_err());
+		assert!(Phone::from_str("+49 1234567890123456").is_err());
+	}
+
+	#[test]
+	fn formatting() {
+		let mobile = Phone::from_str("015758390361").unwrap();
+		assert_eq!(mobile.to_string(), "01575 8390361");
+		assert_eq!(mobile.tel_uri(), "tel:+4915758390361");
+		let berlin = Phone::from_str("+49 30 1234567").unwrap();
+		assert_eq!(berlin.to_string(), "030 1234567");
+		let essen = Phone::from_str("0201/123456").unwrap();
+		assert_eq!(essen.to_string(), "0201 123456");
+		let heidelberg = Phone::from_str("06221 123456").unwrap();
+		assert_eq!(heidelberg.to_string(), "06221 123456");
+		let mut paris = Phone::from_str("+33 1 42 68 12 34").unwrap();
+		paris.add_label(PhoneLabel::Praxis);
+		assert_eq!(paris.to_string(), "+33 142681234 (Praxis)");
+		assert_eq!(paris.e164(), "+33142681234");
+		let local = Phone::parse("0142681234", 33).unwrap();
+		assert!(local.same_number(&paris));
+		assert!(local.ends_with("681234"));
+	}
+
+	#[test]
+	fn longest_area_code() {
+		let codes = ["61", "6151", "615"];
+		assert_eq!(area_code_len("6151374815", codes), Some(4));
+		assert_eq!(area_code_len("6131374815", codes), Some(2));
+		assert_eq!(area_code_len("6151", codes), Some(3));
+		assert_eq!(area_code_len("7151374815", codes), None);
+	}
+
+	#[test]
+	fn bundled_area_codes() {
+		let codes: Vec<&str> = area_codes().collect();
+		assert!(codes.contains(&"6151"));
+		assert!(!codes.contains(&"Ortsnetzkennzahl"));
+		assert!(codes.iter().all(|x| !x.starts_with('1')));
+		// Area codes are prefix free, i.e. each is only its own prefix
+		for code in &codes {
+			let digits = code.bytes().all(|c| c.is_ascii_digit());
+			assert!(digits, "{}", code);
+			let prefix = |x: &&&str| code.starts_with(**x);
+			let prefixes = codes.iter().filter(prefix).count();
+			assert_eq!(prefixes, 1, "{}", code);
+		}
+	}
+
+	#[test]
+	fn mobile_codes() {
+		let numbers = [
+			"01511 1234567",
+			"01556 1234567",
+			"01566 1234567",
+			"01590 1234567",
+			"01595 1234567",
+			"0160 1234567",
+			"0176 12345678",
+			"0179 1234567",
+		];
+		for number in numbers {
+			let phone = Phone::from_str(number).unwrap();
+			assert_eq!(phone.to_string(), number);
+		}
+		assert_eq!(mobile_code_len("1556"), None);
+		assert_eq!(mobile_code_len("6151374815"), None);
+	}
+}
@@ -0,0 +1,262 @@
+//! Grouping offices that share a phone into practices.
+//!
+//! Several therapists often share the switchboard of their practice, which
+//! would otherwise show up once per therapist and be called several times.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::phone::Phone;
+use crate::profile::{Fit, Profile};
+use crate::time::Ranges;
+use crate::{Office, Phones};
+
+/// Offices sharing at least one phone, directly or through other offices
+/// of the practice.
+///
+/// Since each of the offices has its own hours, the practice is reachable
+/// whenever one of them is, i.e. its hours are the union of theirs.
+#[derive(Debug, Clone)]
+pub struct Practice {
+	offices: Vec<Office>,
+	/// The hours of the practice on some day, if they are to be shown.
+	hours: Option<Ranges>,
+	/// How well each of the offices fits a profile, if they have been
+	/// ranked by one.
+	fits: Vec<Fit>,
+}
+
+impl Practice {
+	/// The phones of all offices of the practice, each number only once
+	/// with the labels given to it by any of the offices.
+	fn phones(&self) -> Phones {
+		let mut data: Vec<Phone> = Vec::new();
+		let phones = self.offices.iter().flat_map(|x| &x.phones.data);
+		for phone in phones {
+			let same = |x: &&mut Phone| phone.same_number(x);
+			let Some(known) = data.iter_mut().find(same) else {
+				data.push(phone.clone());
+				continue;
+			};
+			for label in phone.labels() {
+				known.add_label(label.clone());
+			}
+		}
+		Phones::from(data)
+	}
+
+	/// The union of the hours of the offices of the practice, given the
+	/// hours of each office.
+	pub fn hours<F>(&self, hours_of: F) -> Ranges
+	where
+		F: Fn(&Office) -> Ranges,
+	{
+		let mut hours = Ranges::default();
+		for office in &self.offices {
+			hours.extend(hours_of(office));
+		}
+		hours
+	}
+
+	/// The key by which practices are ranked, that of the best fitting
+	/// office (see `Fit::rank_key`).
+	fn rank_key(&self) -> Option<(usize, usize)> {
+		self.fits.iter().map(Fit::rank_key).min()
+	}
+}
+
+impl fmt::Display for Practice {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		for office in &self.offices {
+			writeln!(fmt, "{}", office.names)?;
+		}
+		writeln!(fmt, "{}", self.phones())?;
+		if let Some(hours) = &self.hours {
+			writeln!(fmt, "Hours: {}", hours)?;
+		}
+		let perfect = Fit::default();
+		for (i, office) in self.offices.iter().enumerate() {
+			let fit = self.fits.get(i).unwrap_or(&perfect);
+			let details = Details { data: office }.to_string();
+			if self.offices.len() > 1 {
+				if details.is_empty() && fit.is_perfect() {
+					continue;
+				}
+				writeln!(fmt, "{}:", office.names)?;
+			}
+			write!(fmt, "{}{}", details, fit)?;
+		}
+		Ok(())
+	}
+}
+
+/// Everything but the names and phones of an office, which are shown once
+/// for the whole practice.
+struct Details<'a> {
+	data: &'a Office,
+}
+
+impl fmt::Display for Details<'_> {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		self.data.fmt_details(fmt)
+	}
+}
+
+/// A set of `Practice`s.
+#[derive(Debug)]
+pub struct Practices {
+	data: Vec<Practice>,
+}
+
+/// The representative of the set containing `i` in a union-find forest.
+fn find(parents: &mut [usize], mut i: usize) -> usize {
+	while parents[i] != i {
+		parents[i] = parents[parents[i]];
+		i = parents[i];
+	}
+	i
+}
+
+impl Practices {
+	/// Group the offices into practices, keeping the order of the offices
+	/// (practices are ordered by their first office).
+	pub fn group(offices: &[Office]) -> Practices {
+		let mut parents: Vec<usize> = (0..offices.len()).collect();
+		let mut first_with = HashMap::new();
+		for (i, office) in offices.iter().enumerate() {
+			for phone in &office.phones.data {
+				let key = phone.e164();
+				let first = *first_with.entry(key).or_insert(i);
+				let a = find(&mut parents, first);
+				let b = find(&mut parents, i);
+				parents[a.max(b)] = a.min(b);
+			}
+		}
+		let mut data: Vec<Practice> = Vec::new();
+		let mut practice_of_root = HashMap::new();
+		for (i, office) in offices.iter().enumerate() {
+			let root = find(&mut parents, i);
+			let entry = practice_of_root.entry(root);
+			let index = *entry.or_insert_with(|| {
+				data.push(Practice {
+					offices: Vec::new(),
+					hours: None,
+					fits: Vec::new(),
+				});
+				data.len() - 1
+			});
+			data[index].offices.push(office.clone());
+		}
+		Practices { data }
+	}
+
+	/// The practices with their hours (see `Practice::hours`) to be shown,
+	/// given the hours of each office.
+	pub fn with_hours<F>(mut self, hours_of: F) -> Practices
+	where
+		F: Fn(&Office) -> Ranges,
+	{
+		for practice in &mut self.data {
+			practice.hours = Some(practice.hours(&hours_of));
+		}
+		self
+	}
+
+	/// The practices ranked by how well their best fitting office fits the
+	/// profile, keeping their order among equally fitting ones, with the
+	/// explanation of each mismatch (see `profile::Ranking`).
+	pub fn ranked(mut self, profile: &Profile) -> Practices {
+		for practice in &mut self.data {
+			let fits = practice.offices.iter().map(|x| {
+				profile.fit(x)
+			});
+			practice.fits = fits.collect();
+		}
+		self.data.sort_by_key(Practice::rank_key);
+		self
+	}
+}
+
+impl fmt::Display for Practices {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		for p in &self.data {
+			writeln!(fmt, "{}", p)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use super::*;
+	use crate::hrdb;
+	use crate::phone::PhoneLabel;
+	use crate::time::Channel;
+	use ::time::{Date, Month};
+
+	#[test]
+	fn shared_phones() {
+		let input = "A\n06151 111\n\nB\n06151 222\n\n\
+			C\n06151 333, 06151 111\n\nD\n06151 222 (Praxis)\n\n\
+			E\n06151 444, 06151 333\n";
+		let offices = hrdb::offices(input).unwrap();
+		let practices = offices.practices();
+		let sizes = practices.data.iter().map(|x| x.offices.len());
+		assert_eq!(sizes.collect::<Vec<_>>(), [3, 2]);
+		let expected = "A\nC\nE\n06151 111, 06151 333, 06151 444\n\n\
+			B\nD\n06151 222 (Praxis)\n\n";
+		assert_eq!(practices.to_string(), expected);
+	}
+
+	#[test]
+	fn hours_union() {
+		let input = "A\n06151 111 (Praxis)\nMo: 9:00 – 11:00\n\
+			Di: 9:00 – 10:00\n\n\
+			B\n06151 111 (Mobil)\n\
+			Mo: 10:00 – 12:00, 14:00 – 15:00\n";
+		let offices = hrdb::offices(input).unwrap();
+		// The 19th of October 2026 is a Monday
+		let monday = Date::from_calendar_date(2026, Month::October, 19)
+			.unwrap();
+		let practices = offices.practices_on(&monday, &Channel::Phone);
+		assert_eq!(practices.data.len(), 1);
+		let expected = "A\nB\n06151 111 (Praxis, Mobil)\n\
+			Hours: 9:00 – 12:00, 14:00 – 15:00\n\n";
+		assert_eq!(practices.to_string(), expected);
+		let tuesday = monday.next_day().unwrap();
+		let hours = practices.data[0].hours(|x| {
+			x.hours_on(&tuesday, &Channel::Phone, |_| false)
+		});
+		assert_eq!(hours.to_string(), "9:00 – 10:00");
+	}
+
+	#[test]
+	fn merged_labels() {
+		let input = "A\n06151 111 (Praxis)\n\nB\n06151 111 (Mobil)\n";
+		let offices = hrdb::offices(input).unwrap();
+		let practices = offices.practices();
+		let phones = practices.data[0].phones();
+		let labels = [PhoneLabel::Praxis, PhoneLabel::Mobil];
+		assert_eq!(phones.data[0].labels(), labels);
+		let mut hidden = phones.clone();
+		hidden.hide(&[PhoneLabel::Mobil]);
+		assert!(hidden.data.is_empty());
+	}
+
+	#[test]
+	fn ranked() {
+		let input = "A\n06151 111\nKasse: PKV\n\n\
+			B\n06151 222\nKasse: PKV\n\n\
+			C\n06151 111\nKasse: GKV\n";
+		let offices = hrdb::offices(input).unwrap();
+		let profile = Profile::from_str("Kasse: GKV").unwrap();
+		let practices = offices.practices().ranked(&profile);
+		let expected = "A\nC\n06151 111\n\
+			A:\nKasse: PKV\n- does not accept GKV\n\
+			C:\nKasse: GKV\n\n\
+			B\n06151 222\nKasse: PKV\n- does not accept GKV\n\n";
+		assert_eq!(practices.to_string(), expected);
+	}
+}
@@ -0,0 +1,510 @@
+//! Ranking offices by how well they fit the needs of the person seeking
+//! therapy.
+//!
+//! The needs are declared in a profile, a small text file in the style of
+//! the metadata lines of the database, e.g.
+//!
+//! ```text
+//! # Wer sucht?
+//! Kasse: GKV, Kostenerstattung
+//! Verfahren: VT, TP
+//! Alter: Erwachsene
+//! Sprachen: Deutsch, Englisch
+//! Ort: 49.8728, 8.6512
+//! Entfernung: 10 km
+//! ```
+//!
+//! All lines are optional. Lists give the acceptable alternatives, e.g. an
+//! office offering either VT or TP fits the profile above, and so does an
+//! office accepting either GKV or Kostenerstattung. These needs are
+//! compared with the `Kasse:`, `Verfahren:`, `Alter:` and `Sprachen:` lines
+//! of the offices. The `Entfernung:` is measured from the `Ort:` of the
+//! profile, i.e. where the person seeking lives, to the location of the
+//! offices (see `geo`), so it needs an `Ort:`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::geo::Coordinates;
+use crate::{Office, Offices, Tags};
+
+/// The way therapy is paid for.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Insurance {
+	/// By the statutory health insurance (gesetzliche Krankenversicherung).
+	GKV,
+	/// By a private health insurance (private Krankenversicherung).
+	PKV,
+	/// By the statutory health insurance reimbursing the costs of a
+	/// therapist without a license to bill it directly.
+	Kostenerstattung,
+}
+
+const INSURANCES: [Insurance; 3] =
+	[Insurance::GKV, Insurance::PKV, Insurance::Kostenerstattung];
+
+#[derive(Debug)]
+pub enum InsuranceErr {
+	Unknown,
+}
+
+impl FromStr for Insurance {
+	type Err = InsuranceErr;
+
+	/// Parses the names of the insurances ignoring case.
+	fn from_str(src: &str) -> Result<Insurance, Self::Err> {
+		INSURANCES
+			.into_iter()
+			.find(|x| x.to_string().eq_ignore_ascii_case(src))
+			.ok_or(InsuranceErr::Unknown)
+	}
+}
+
+impl fmt::Display for Insurance {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Insurance::GKV => write!(fmt, "GKV"),
+			Insurance::PKV => write!(fmt, "PKV"),
+			Insurance::Kostenerstattung => {
+				write!(fmt, "Kostenerstattung")
+			}
+		}
+	}
+}
+
+/// The needs of a person seeking therapy, see the module documentation for
+/// the format.
+#[derive(Debug, PartialEq, Default)]
+pub struct Profile {
+	/// The acceptable insurances, e.g. `[GKV, Kostenerstattung]`.
+	insurances: Vec<Insurance>,
+	/// The acceptable therapy methods, e.g. `["VT", "TP"]`.
+	methods: Vec<String>,
+	/// The age groups of which the therapist should treat any.
+	age_groups: Vec<String>,
+	/// The languages of which the therapist should speak any.
+	languages: Vec<String>,
+	/// Where the person seeking lives.
+	origin: Option<Coordinates>,
+	/// The maximal distance from the origin to the office in kilometres.
+	max_distance: Option<f64>,
+}
+
+impl Profile {
+	/// How well the office fits the profile.
+	pub fn fit(&self, office: &Office) -> Fit {
+		let mut fit = Fit::default();
+		let wanted = &self.insurances;
+		let accepted = |x| office.insurances.contains(x);
+		if wanted.is_empty() {
+			// Any insurance fits
+		} else if office.insurances.is_empty() {
+			fit.unknown.push(Need::Insurance);
+		} else if !wanted.iter().any(accepted) {
+			let mismatch = Mismatch::Insurance(wanted.clone());
+			fit.mismatches.push(mismatch);
+		}
+		fit.compare(Need::Method, &self.methods, &office.methods);
+		let age_groups = &office.age_groups;
+		fit.compare(Need::AgeGroup, &self.age_groups, age_groups);
+		fit.compare(Need::Language, &self.languages, &office.languages);
+		if let Some(origin) = &self.origin {
+			if let Some(max) = self.max_distance {
+				fit.measure(office, origin, max);
+			}
+		}
+		fit
+	}
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProfileErrKind {
+	UnknownKey(String),
+	InvalidInsurance,
+	InvalidDistance,
+	InvalidLocation,
+	/// An `Entfernung:` without an `Ort:` to measure it from.
+	MissingLocation,
+	/// A line that is neither a `Key: value` pair nor a comment.
+	InvalidLine,
+}
+
+/// Why a profile could not be parsed, and in which line.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ProfileErr {
+	line: usize,
+	kind: ProfileErrKind,
+}
+
+impl fmt::Display for ProfileErr {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "line {}: ", self.line)?;
+		match &self.kind {
+			ProfileErrKind::UnknownKey(key) => {
+				write!(fmt, "unknown key \"{}\"", key)
+			}
+			ProfileErrKind::InvalidInsurance => {
+				let expected = "GKV, PKV or Kostenerstattung";
+				write!(fmt, "expected {}", expected)
+			}
+			ProfileErrKind::InvalidDistance => {
+				write!(fmt, "expected a distance like 10 km")
+			}
+			ProfileErrKind::InvalidLocation => {
+				let expected = "coordinates like 49.9, 8.7";
+				write!(fmt, "expected {}", expected)
+			}
+			ProfileErrKind::MissingLocation => {
+				write!(fmt, "expected an Ort: to measure from")
+			}
+			ProfileErrKind::InvalidLine => {
+				write!(fmt, "expected a line like Kasse: GKV")
+			}
+		}
+	}
+}
+
+/// The comma separated items of a list, e.g. `"VT, TP"`.
+fn items(src: &str) -> Vec<String> {
+	src.split(',')
+		.map(str::trim)
+		.filter(|x| !x.is_empty())
+		.map(String::from)
+		.collect()
+}
+
+/// The insurances of a profile, e.g. `"GKV, Kostenerstattung"`.
+fn insurances(src: &str) -> Result<Vec<Insurance>, ProfileErrKind> {
+	items(src)
+		.iter()
+		.map(|x| Insurance::from_str(x))
+		.collect::<Result<_, _>>()
+		.map_err(|_| ProfileErrKind::InvalidInsurance)
+}
+
+/// A distance in kilometres, e.g. `"7,5 km"` or `"10"`, as given in a
+/// profile or to `--within`. Negative and non-finite distances are invalid.
+pub fn distance(src: &str) -> Result<f64, ProfileErrKind> {
+	let km = src.trim_end_matches("km").trim().replace(',', ".");
+	km.parse()
+		.ok()
+		.filter(|x: &f64| x.is_finite() && *x >= 0.0)
+		.ok_or(ProfileErrKind::InvalidDistance)
+}
+
+impl Profile {
+	/// Set the need given by a line of a profile.
+	fn set(
+		&mut self,
+		key: &str,
+		value: &str,
+	) -> Result<(), ProfileErrKind> {
+		match key {
+			"Kasse" => self.insurances = insurances(value)?,
+			"Verfahren" => self.methods = items(value),
+			"Alter" => self.age_groups = items(value),
+			"Sprachen" => self.languages = items(value),
+			"Ort" => {
+				let origin = Coordinates::from_str(value);
+				let invalid = ProfileErrKind::InvalidLocation;
+				self.origin = Some(origin.or(Err(invalid))?);
+			}
+			"Entfernung" => {
+				self.max_distance = Some(distance(value)?)
+			}
+			_ => {
+				let key = String::from(key);
+				return Err(ProfileErrKind::UnknownKey(key));
+			}
+		}
+		Ok(())
+	}
+}
+
+impl FromStr for Profile {
+	type Err = ProfileErr;
+
+	/// Parses a profile, ignoring empty lines and comments starting with
+	/// `"#"`.
+	///
+	/// An `Entfernung:` is only valid together with an `Ort:`.
+	fn from_str(src: &str) -> Result<Profile, Self::Err> {
+		let mut profile = Profile::default();
+		let mut distance_line = None;
+		for (index, line) in src.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let result = match line.split_once(':') {
+				Some((key, value)) => {
+					if key == "Entfernung" {
+						distance_line = Some(index + 1);
+					}
+					profile.set(key, value.trim())
+				}
+				None => Err(ProfileErrKind::InvalidLine),
+			};
+			let line = index + 1;
+			result.map_err(|kind| ProfileErr { line, kind })?;
+		}
+		match distance_line {
+			Some(line) if profile.origin.is_none() => {
+				let kind = ProfileErrKind::MissingLocation;
+				Err(ProfileErr { line, kind })
+			}
+			_ => Ok(profile),
+		}
+	}
+}
+
+/// A need of the profile.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Need {
+	Insurance,
+	Method,
+	AgeGroup,
+	Language,
+	Distance,
+}
+
+impl fmt::Display for Need {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Need::Insurance => write!(fmt, "insurance"),
+			Need::Method => write!(fmt, "therapy methods"),
+			Need::AgeGroup => write!(fmt, "age groups"),
+			Need::Language => write!(fmt, "languages"),
+			Need::Distance => write!(fmt, "location"),
+		}
+	}
+}
+
+/// A need of the profile that an office does not meet.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Mismatch {
+	/// The office accepts none of the wanted insurances.
+	Insurance(Vec<Insurance>),
+	/// The office offers none of the wanted methods, age groups or
+	/// languages.
+	Other(Need, Vec<String>),
+	/// The office is farther away (in kilometres) than the maximal
+	/// distance.
+	TooFar(f64, f64),
+}
+
+impl fmt::Display for Mismatch {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Mismatch::Insurance(wanted) => {
+				let all = wanted.iter().map(|x| x.to_string());
+				let all: Vec<String> = all.collect();
+				let wanted = all.join(" or ");
+				write!(fmt, "does not accept {}", wanted)
+			}
+			Mismatch::Other(need, wanted) => {
+				let verb = match need {
+					Need::Method => "offers none of",
+					Need::AgeGroup => "treats none of",
+					_ => "speaks none of",
+				};
+				write!(fmt, "{} {}", verb, wanted.join(", "))
+			}
+			Mismatch::TooFar(km, max) => write!(
+				fmt,
+				"is {:.1} km away, farther than {} km",
+				km, max,
+			),
+		}
+	}
+}
+
+/// How well an office fits a profile.
+#[derive(Debug, PartialEq, Default, Clone)]
+pub struct Fit {
+	mismatches: Vec<Mismatch>,
+	/// The needs the database has no information about for the office.
+	unknown: Vec<Need>,
+}
+
+impl Fit {
+	/// Note whether any of the wanted items of a need is offered.
+	fn compare(&mut self, need: Need, wanted: &[String], offered: &Tags) {
+		if wanted.is_empty() {
+			return;
+		}
+		if offered.data.is_empty() {
+			self.unknown.push(need);
+		} else if !wanted.iter().any(|x| offered.contains(x)) {
+			let wanted = wanted.to_vec();
+			self.mismatches.push(Mismatch::Other(need, wanted));
+		}
+	}
+
+	/// Note whether the office is within the maximal distance (in
+	/// kilometres) from the origin.
+	fn measure(&mut self, office: &Office, origin: &Coordinates, max: f64) {
+		match office.coordinates().map(|x| x.distance(origin)) {
+			None => self.unknown.push(Need::Distance),
+			Some(km) if km > max => {
+				self.mismatches.push(Mismatch::TooFar(km, max))
+			}
+			Some(_) => {}
+		}
+	}
+
+	/// The key by which offices are ranked, best fits first: those with
+	/// fewer mismatches, then those with less unknown.
+	pub(crate) fn rank_key(&self) -> (usize, usize) {
+		(self.mismatches.len(), self.unknown.len())
+	}
+
+	/// Whether the office fits all needs, as far as is known.
+	pub fn is_perfect(&self) -> bool {
+		self.mismatches.is_empty() && self.unknown.is_empty()
+	}
+}
+
+impl fmt::Display for Fit {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		for mismatch in &self.mismatches {
+			writeln!(fmt, "- {}", mismatch)?;
+		}
+		for need in &self.unknown {
+			writeln!(fmt, "? no information on {}", need)?;
+		}
+		Ok(())
+	}
+}
+
+/// Offices ranked by how well they fit a profile, with the explanation of
+/// each mismatch.
+#[derive(Debug)]
+pub struct Ranking {
+	data: Vec<(Office, Fit)>,
+}
+
+impl Ranking {
+	/// Rank the offices, keeping their order among equally fitting ones.
+	pub fn new(offices: &Offices, profile: &Profile) -> Ranking {
+		let mut data: Vec<(Office, Fit)> = offices
+			.data
+			.iter()
+			.map(|x| (x.clone(), profile.fit(x)))
+			.collect();
+		data.sort_by_key(|(_, fit)| fit.rank_key());
+		Ranking { data }
+	}
+}
+
+impl fmt::Display for Ranking {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		for (office, fit) in &self.data {
+			writeln!(fmt, "{}{}", office, fit)?;
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::hrdb;
+
+	#[test]
+	fn parsing() {
+		let input = "# Wer sucht?\nKasse: gkv\nVerfahren: VT, TP\n\n\
+			Alter: Erwachsene\nOrt: 49.8728, 8.6512\n\
+			Entfernung: 7,5 km\n";
+		let profile = Profile::from_str(input).unwrap();
+		assert_eq!(profile.insurances, [Insurance::GKV]);
+		assert_eq!(profile.methods, ["VT", "TP"]);
+		assert_eq!(profile.age_groups, ["Erwachsene"]);
+		assert!(profile.languages.is_empty());
+		assert_eq!(profile.max_distance, Some(7.5));
+		let input = "Kasse: GKV, Kostenerstattung\n\
+			Alter: Kinder, Jugendliche";
+		let profile = Profile::from_str(input).unwrap();
+		let expected = [Insurance::GKV, Insurance::Kostenerstattung];
+		assert_eq!(profile.insurances, expected);
+		assert_eq!(profile.age_groups, ["Kinder", "Jugendliche"]);
+		let error = |input| Profile::from_str(input).unwrap_err();
+		let expected = "line 2: unknown key \"Farbe\"";
+		let input = "Kasse: PKV\nFarbe: blau";
+		assert_eq!(error(input).to_string(), expected);
+		let expected = ProfileErrKind::InvalidDistance;
+		assert_eq!(error("Entfernung: weit").kind, expected);
+		assert_eq!(super::distance("7,5"), Ok(7.5));
+		assert_eq!(super::distance("10 km"), Ok(10.0));
+		for invalid in ["NaN", "-1", "inf", "-0,5 km"] {
+			let expected = Err(ProfileErrKind::InvalidDistance);
+			assert_eq!(super::distance(invalid), expected);
+		}
+		let expected = "line 2: expected an Ort: to measure from";
+		let input = "Kasse: GKV\nEntfernung: 5 km";
+		assert_eq!(error(input).to_string(), expected);
+		let expected = ProfileErrKind::InvalidLocation;
+		assert_eq!(error("Ort: Darmstadt").kind, expected);
+		let expected = ProfileErrKind::InvalidInsurance;
+		assert_eq!(error("Kasse: AOK").kind, expected);
+		assert_eq!(error("VT").kind, ProfileErrKind::InvalidLine);
+	}
+
+	#[test]
+	fn ranking() {
+		let input = "A\n06151 111\nKasse: PKV\nVerfahren: VT\n\n\
+			B\n06151 222\nKasse: GKV, PKV\nVerfahren: TP\n\
+			Alter: Kinder, Jugendliche\n\n\
+			C\n06151 333\nKasse: GKV\nVerfahren: VT, ST\n";
+		let offices = hrdb::offices(input).unwrap();
+		let profile = "Kasse: GKV\nVerfahren: VT, AP\nAlter: Kinder";
+		let profile = Profile::from_str(profile).unwrap();
+		let ranking = Ranking::new(&offices, &profile);
+		let expected = "C\n06151 333\nKasse: GKV\nVerfahren: ST, VT\n\
+			? no information on age groups\n\n\
+			B\n06151 222\nKasse: GKV, PKV\nVerfahren: TP\n\
+			Alter: Jugendliche, Kinder\n\
+			- offers none of VT, AP\n\n\
+			A\n06151 111\nKasse: PKV\nVerfahren: VT\n\
+			- does not accept GKV\n\
+			? no information on age groups\n\n";
+		assert_eq!(ranking.to_string(), expected);
+		let profile = "Kasse: GKV, Kostenerstattung\n\
+			Alter: Erwachsene, Kinder";
+		let profile = Profile::from_str(profile).unwrap();
+		let fits: Vec<String> = offices
+			.data
+			.iter()
+			.map(|x| profile.fit(x).to_string())
+			.collect();
+		let expected = [
+			"- does not accept GKV or Kostenerstattung\n\
+				? no information on age groups\n",
+			"",
+			"? no information on age groups\n",
+		];
+		assert_eq!(fits, expected);
+	}
+
+	#[test]
+	fn distance() {
+		let input = "Nah\n06151 111\nOrt: 49.8728, 8.6512\n\n\
+			Fern\n06151 222\nOrt: 49.8150, 8.6450\n\n\
+			Unbekannt\n06151 333\n\
+			Adresse: Rheinstraße 1, 64283 Darmstadt\n";
+		let offices = hrdb::offices(input).unwrap();
+		let profile = "Ort: 49.8750, 8.6500\nEntfernung: 5 km";
+		let profile = Profile::from_str(profile).unwrap();
+		let fits: Vec<String> = offices
+			.data
+			.iter()
+			.map(|x| profile.fit(x).to_string())
+			.collect();
+		let expected = [
+			"",
+			"- is 6.7 km away, farther than 5 km\n",
+			"? no information on location\n",
+		];
+		assert_eq!(fits, expected);
+	}
+}
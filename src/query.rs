@@ -0,0 +1,674 @@
+//! A small language for filtering offices, e.g.
+//! `day=Di and from>=9:00 and not name=Müller`.
+//!
+//! A query consists of conditions of the form `field op value`, combined
+//! by `and`, `or` and `not` (binding in the order `not`, `and`, `or`) and
+//! grouped by parentheses. The fields are
+//!
+//! - `day`, `from` and `until`, comparing the day, begin and end of office
+//!   hours with a day like `Di` or a time like `9:00` using any of `=`,
+//!   `!=`, `<`, `<=`, `>` and `>=`,
+//! - `channel`, comparing the channel of office hours with `=` or `!=`,
+//! - `name`, whether any name of the office contains the value (ignoring
+//!   case and umlauts, see `search::fold`), with `=` or `!=`,
+//! - `phone`, whether the office has the given phone number or one ending
+//!   in its digits, with `=` or `!=`,
+//! - `tag`, whether the office has the given tag (ignoring case), with `=`
+//!   or `!=`, and
+//! - `status`, comparing the outcome of the last call to the office (see
+//!   `Offices::with_status`) with `=` or `!=`, e.g. `status=Absage`.
+//!
+//! Values containing spaces need to be quoted, as in `name="Iris P"`.
+//!
+//! Conditions on office hours all have to hold for the same office hour,
+//! so that `day=Di and from>=9:00` finds offices with hours from nine on
+//! Tuesdays, not offices with some hours on Tuesday and others from nine.
+//! The office hours taken into account are those of the schedule valid on
+//! the date the query is run and of schedules starting later, not those
+//! that have expired or been superseded. Extra hours are not taken into
+//! account, since they are on a single date rather than on a day of every
+//! week.
+//!
+//! The operator `!=` also compares a single office hour, whereas `not`
+//! applies to the office as a whole: `day!=Di` finds offices with some
+//! hours on another day than Tuesday, `not day=Di` those without any hours
+//! on Tuesday. An office without any hours thus matches `not day=Di` but
+//! not `day!=Di`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use ::time::Date;
+use nom::error::{ContextError, ErrorKind, FromExternalError, ParseError};
+
+use crate::calls::Outcome;
+use crate::hrdb;
+use crate::search;
+use crate::time::{Channel, Clock, Day};
+use crate::Office;
+
+/// What the parser expected where it failed, if known.
+#[derive(Debug)]
+struct Expected<'a> {
+	input: &'a str,
+	what: Option<&'static str>,
+}
+
+impl<'a> ParseError<&'a str> for Expected<'a> {
+	fn from_error_kind(input: &'a str, _: ErrorKind) -> Self {
+		Expected { input, what: None }
+	}
+
+	fn append(_: &'a str, _: ErrorKind, other: Self) -> Self {
+		other
+	}
+
+	/// Of two alternatives, report the one which got further, or else the
+	/// one knowing what it expected.
+	fn or(self, other: Self) -> Self {
+		let further = other.input.len() < self.input.len();
+		let as_far = other.input.len() == self.input.len();
+		if further || (as_far && self.what.is_none()) {
+			other
+		} else {
+			self
+		}
+	}
+}
+
+impl<'a> ContextError<&'a str> for Expected<'a> {
+	/// Keep the innermost context, as it is the most specific one.
+	fn add_context(
+		input: &'a str,
+		what: &'static str,
+		other: Self,
+	) -> Self {
+		match other.what {
+			Some(_) => other,
+			None => Expected { input, what: Some(what) },
+		}
+	}
+}
+
+impl<'a, E> FromExternalError<&'a str, E> for Expected<'a> {
+	fn from_external_error(input: &'a str, kind: ErrorKind, _: E) -> Self {
+		Expected::from_error_kind(input, kind)
+	}
+}
+
+type IResult<'a, O> = nom::IResult<&'a str, O, Expected<'a>>;
+
+/// What is expected where a condition starts.
+const FIELD: &str =
+	"a field like day, from, until, channel, name, phone, tag or status";
+
+/// An operator comparing a field with a value.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Op {
+	Eq,
+	Ne,
+	Lt,
+	Le,
+	Gt,
+	Ge,
+}
+
+impl Op {
+	fn compare<T: Ord>(&self, field: &T, value: &T) -> bool {
+		match self {
+			Op::Eq => field == value,
+			Op::Ne => field != value,
+			Op::Lt => field < value,
+			Op::Le => field <= value,
+			Op::Gt => field > value,
+			Op::Ge => field >= value,
+		}
+	}
+
+	/// Apply an equality operator to whether the field equals the value.
+	fn equals(&self, equal: bool) -> bool {
+		match self {
+			Op::Ne => !equal,
+			_ => equal,
+		}
+	}
+}
+
+/// A single comparison of a field with a value.
+#[derive(Debug, Clone)]
+enum Condition {
+	Day(Op, Day),
+	From(Op, Clock),
+	Until(Op, Clock),
+	Channel(Op, Channel),
+	Name(Op, String),
+	Phone(Op, String),
+	Tag(Op, String),
+	Status(Op, Outcome),
+}
+
+/// Whether any name of the office contains the given one, see
+/// `search::fold`.
+fn has_name(office: &Office, name: &str) -> bool {
+	let name = search::fold(name);
+	office
+		.names
+		.iter()
+		.any(|x| search::fold(&x.to_string()).contains(&name))
+}
+
+/// The times of office hours compared by conditions.
+#[derive(Debug)]
+struct Hours<'a> {
+	day: Day,
+	begin: &'a Clock,
+	end: &'a Clock,
+	channel: &'a Channel,
+}
+
+/// The office hours of the office valid at the given date or later (see
+/// `Schedules::hours_from`).
+fn hours<'a>(office: &'a Office, today: &'a Date) -> Vec<Hours<'a>> {
+	let hours = office.schedules.hours_from(today).map(|x| Hours {
+		day: x.day().clone(),
+		begin: x.begin(),
+		end: x.end(),
+		channel: x.channel(),
+	});
+	hours.collect()
+}
+
+impl Condition {
+	fn holds(&self, office: &Office, hour: Option<&Hours>) -> bool {
+		match self {
+			Condition::Day(op, day) => hour.is_some_and(|x| {
+				op.compare(&x.day, day)
+			}),
+			Condition::From(op, clock) => hour.is_some_and(|x| {
+				op.compare(x.begin, clock)
+			}),
+			Condition::Until(op, clock) => hour.is_some_and(|x| {
+				op.compare(x.end, clock)
+			}),
+			Condition::Channel(op, channel) => {
+				let same = hour.map(|x| x.channel == channel);
+				same.is_some_and(|same| op.equals(same))
+			}
+			Condition::Name(op, name) => {
+				op.equals(has_name(office, name))
+			}
+			Condition::Phone(op, number) => {
+				op.equals(office.has_phone(number, false))
+			}
+			Condition::Tag(op, tag) => {
+				op.equals(office.tags.contains(tag))
+			}
+			Condition::Status(op, outcome) => {
+				op.equals(office.status == Some(*outcome))
+			}
+		}
+	}
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+	Condition(Condition),
+	Not(Box<Expr>),
+	And(Box<Expr>, Box<Expr>),
+	Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+	/// Whether the expression holds for the office and one of its hours,
+	/// if it has any.
+	///
+	/// A negation holds if the negated expression does not hold for the
+	/// office with any of its hours.
+	fn holds(
+		&self,
+		office: &Office,
+		hour: Option<&Hours>,
+		today: &Date,
+	) -> bool {
+		match self {
+			Expr::Condition(c) => c.holds(office, hour),
+			Expr::Not(e) => !e.matches(office, today),
+			Expr::And(a, b) => {
+				a.holds(office, hour, today)
+					&& b.holds(office, hour, today)
+			}
+			Expr::Or(a, b) => {
+				a.holds(office, hour, today)
+					|| b.holds(office, hour, today)
+			}
+		}
+	}
+
+	/// Whether the expression holds for the office with any of its hours
+	/// valid at the given date or later, or without any if it has none.
+	fn matches(&self, office: &Office, today: &Date) -> bool {
+		let hours = hours(office, today);
+		if hours.is_empty() {
+			self.holds(office, None, today)
+		} else {
+			let holds = |x| self.holds(office, Some(x), today);
+			hours.iter().any(holds)
+		}
+	}
+}
+
+/// Turn a parser of the `hrdb` module into one for queries.
+fn lift<'a, O>(
+	mut parser: impl FnMut(&'a str) -> nom::IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, O> {
+	move |input| {
+		parser(input).map_err(|e| {
+			e.map(|e| Expected::from_error_kind(e.input, e.code))
+		})
+	}
+}
+
+/// Nom parser for a keyword like `and`, which may not be followed by
+/// further letters.
+fn keyword<'a>(word: &'static str) -> impl FnMut(&'a str) -> IResult<'a, ()> {
+	nom::combinator::value(
+		(),
+		nom::sequence::delimited(
+			nom::character::complete::multispace0,
+			nom::sequence::terminated(
+				nom::bytes::complete::tag_no_case(word),
+				nom::combinator::not(
+					nom::character::complete::satisfy(
+						char::is_alphanumeric,
+					),
+				),
+			),
+			nom::character::complete::multispace0,
+		),
+	)
+}
+
+/// Nom parser for a value, which is either quoted or ends at whitespace
+/// or a closing parenthesis.
+fn value(input: &str) -> IResult<'_, &str> {
+	nom::error::context(
+		"a value",
+		nom::branch::alt((
+			nom::sequence::delimited(
+				nom::bytes::complete::tag("\""),
+				nom::bytes::complete::is_not("\""),
+				nom::bytes::complete::tag("\""),
+			),
+			nom::bytes::complete::take_till1(|c: char| {
+				c.is_whitespace() || c == ')'
+			}),
+		)),
+	)(input)
+}
+
+/// Nom parser for a value that needs to be parsed completely by the given
+/// parser, e.g. a day.
+fn typed_value<'a, O>(
+	what: &'static str,
+	parser: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, O> {
+	nom::error::context(
+		what,
+		nom::combinator::map_parser(
+			value,
+			nom::combinator::all_consuming(parser),
+		),
+	)
+}
+
+/// Nom parser for any of the comparison operators.
+fn op(input: &str) -> IResult<'_, Op> {
+	let tag = nom::bytes::complete::tag;
+	nom::error::context(
+		"one of =, !=, <, <=, > and >=",
+		nom::branch::alt((
+			nom::combinator::value(Op::Eq, tag("=")),
+			nom::combinator::value(Op::Ne, tag("!=")),
+			nom::combinator::value(Op::Le, tag("<=")),
+			nom::combinator::value(Op::Lt, tag("<")),
+			nom::combinator::value(Op::Ge, tag(">=")),
+			nom::combinator::value(Op::Gt, tag(">")),
+		)),
+	)(input)
+}
+
+/// Nom parser for the operators `=` and `!=`.
+fn equality(input: &str) -> IResult<'_, Op> {
+	nom::error::context(
+		"= or !=",
+		nom::combinator::verify(op, |op| matches!(op, Op::Eq | Op::Ne)),
+	)(input)
+}
+
+/// Nom parser for an operator and a value following a field.
+///
+/// Since the field is already known, anything not fitting it is a failure.
+fn compared<'a, O>(
+	op: impl FnMut(&'a str) -> IResult<'a, Op>,
+	value: impl FnMut(&'a str) -> IResult<'a, O>,
+) -> impl FnMut(&'a str) -> IResult<'a, (Op, O)> {
+	nom::combinator::cut(nom::sequence::pair(
+		nom::sequence::delimited(
+			nom::character::complete::multispace0,
+			op,
+			nom::character::complete::multispace0,
+		),
+		value,
+	))
+}
+
+/// Nom parser for a condition, i.e. a field, an operator and a value.
+fn condition(input: &str) -> IResult<'_, Condition> {
+	let start = input;
+	let (input, field) = nom::error::context(
+		FIELD,
+		nom::character::complete::alpha1,
+	)(input)?;
+	let day = || typed_value("a day like Di", lift(hrdb::day));
+	let clock = || typed_value("a time like 9:00", lift(hrdb::time));
+	let channel = typed_value(
+		"a channel (Telefon, Sprechstunde or Video)",
+		nom::combinator::map_res(
+			nom::character::complete::alpha1,
+			Channel::from_str,
+		),
+	);
+	let text = || nom::combinator::map(value, String::from);
+	let outcome = typed_value(
+		"an outcome like besetzt",
+		nom::combinator::map_res(
+			nom::combinator::rest,
+			Outcome::from_str,
+		),
+	);
+	match field {
+		"day" => nom::combinator::map(
+			compared(op, day()),
+			|(op, day)| Condition::Day(op, day),
+		)(input),
+		"from" => nom::combinator::map(
+			compared(op, clock()),
+			|(op, clock)| Condition::From(op, clock),
+		)(input),
+		"until" => nom::combinator::map(
+			compared(op, clock()),
+			|(op, clock)| Condition::Until(op, clock),
+		)(input),
+		"channel" => nom::combinator::map(
+			compared(equality, channel),
+			|(op, channel)| Condition::Channel(op, channel),
+		)(input),
+		"name" => nom::combinator::map(
+			compared(equality, text()),
+			|(op, name)| Condition::Name(op, name),
+		)(input),
+		"phone" => nom::combinator::map(
+			compared(equality, text()),
+			|(op, number)| Condition::Phone(op, number),
+		)(input),
+		"tag" => nom::combinator::map(
+			compared(equality, text()),
+			|(op, tag)| Condition::Tag(op, tag),
+		)(input),
+		"status" => nom::combinator::map(
+			compared(equality, outcome),
+			|(op, outcome)| Condition::Status(op, outcome),
+		)(input),
+		_ => Err(nom::Err::Failure(Expected {
+			input: start,
+			what: Some(FIELD),
+		})),
+	}
+}
+
+/// Nom parser for a negated or parenthesized expression or a condition.
+fn unary(input: &str) -> IResult<'_, Expr> {
+	nom::sequence::delimited(
+		nom::character::complete::multispace0,
+		nom::branch::alt((
+			nom::combinator::map(
+				nom::sequence::preceded(
+					keyword("not"),
+					nom::combinator::cut(unary),
+				),
+				|e| Expr::Not(Box::new(e)),
+			),
+			nom::sequence::delimited(
+				nom::bytes::complete::tag("("),
+				nom::combinator::cut(expr),
+				nom::combinator::cut(nom::error::context(
+					"a closing parenthesis",
+					nom::bytes::complete::tag(")"),
+				)),
+			),
+			nom::combinator::map(condition, Expr::Condition),
+		)),
+		nom::character::complete::multispace0,
+	)(input)
+}
+
+/// Nom parser for expressions joined by `and`.
+fn conjunction(input: &str) -> IResult<'_, Expr> {
+	let (input, first) = unary(input)?;
+	nom::multi::fold_many0(
+		nom::sequence::preceded(
+			keyword("and"),
+			nom::combinator::cut(unary),
+		),
+		move || first.clone(),
+		|a, b| Expr::And(Box::new(a), Box::new(b)),
+	)(input)
+}
+
+/// Nom parser for expressions joined by `or`.
+fn expr(input: &str) -> IResult<'_, Expr> {
+	let (input, first) = conjunction(input)?;
+	nom::multi::fold_many0(
+		nom::sequence::preceded(
+			keyword("or"),
+			nom::combinator::cut(conjunction),
+		),
+		move || first.clone(),
+		|a, b| Expr::Or(Box::new(a), Box::new(b)),
+	)(input)
+}
+
+/// A compiled query, see the module documentation for its syntax.
+#[derive(Debug, Clone)]
+pub struct Query {
+	expr: Expr,
+}
+
+impl Query {
+	/// Whether the office matches the query run at the given date, which
+	/// decides the office hours taken into account.
+	pub fn matches(&self, office: &Office, today: &Date) -> bool {
+		self.expr.matches(office, today)
+	}
+}
+
+/// Why a query could not be parsed.
+#[derive(Debug, PartialEq, Eq)]
+pub struct QueryErr {
+	/// The position in characters at which the problem was found.
+	position: usize,
+	/// The rest of the query from there.
+	found: String,
+	expected: Option<&'static str>,
+}
+
+impl fmt::Display for QueryErr {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		let found = match self.found.as_str() {
+			"" => String::from("end of query"),
+			found => format!("\"{}\"", found),
+		};
+		match self.expected {
+			Some(expected) => write!(
+				fmt,
+				"expected {} at position {}, found {}",
+				expected, self.position, found,
+			),
+			None => write!(
+				fmt,
+				"unexpected {} at position {}",
+				found, self.position,
+			),
+		}
+	}
+}
+
+impl FromStr for Query {
+	type Err = QueryErr;
+
+	fn from_str(src: &str) -> Result<Query, Self::Err> {
+		let result = nom::combinator::all_consuming(expr)(src);
+		match result {
+			Ok((_, expr)) => Ok(Query { expr }),
+			Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+				let parsed = &src[..src.len() - e.input.len()];
+				Err(QueryErr {
+					position: parsed.chars().count() + 1,
+					found: String::from(e.input.trim_end()),
+					expected: e.what,
+				})
+			}
+			Err(nom::Err::Incomplete(_)) => Err(QueryErr {
+				position: src.chars().count() + 1,
+				found: String::new(),
+				expected: None,
+			}),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::calls::CallLog;
+
+	/// The date the queries are run at, a Monday.
+	fn today() -> Date {
+		Date::from_calendar_date(2026, ::time::Month::October, 19)
+			.unwrap()
+	}
+
+	fn names(input: &str, query: &str) -> Vec<String> {
+		let offices = hrdb::offices(input).unwrap();
+		let query = Query::from_str(query).unwrap();
+		offices
+			.filter(&query, &today())
+			.data
+			.iter()
+			.map(|x| x.names.to_string())
+			.collect()
+	}
+
+	#[test]
+	fn conditions() {
+		let input = "A\n06151 111\n\
+			Mo: 9:00 – 10:00\nDi: 8:00 – 8:30\n\n\
+			B\n06151 222\nDi: 10:00 – 11:00\nTags: VT, Kinder\n\n\
+			Jörg Müller\n06151 333\nVideo Di: 8:00 – 9:00\n";
+		let names = |query| names(input, query);
+		let none: Vec<String> = Vec::new();
+		assert_eq!(names("day=Di"), ["A", "B", "Jörg Müller"]);
+		assert_eq!(names("day=Di and from>=9:00"), ["B"]);
+		let early = ["A", "Jörg Müller"];
+		assert_eq!(names("day = Di and until <= 9:00"), early);
+		assert_eq!(names("day>Mo and channel=video"), ["Jörg Müller"]);
+		assert_eq!(names("not channel=Video"), ["A", "B"]);
+		let either = ["B", "Jörg Müller"];
+		assert_eq!(names("name=mueller or phone=51222"), either);
+		let query = "not (name=a or phone=\"06151 222\")";
+		assert_eq!(names(query), ["Jörg Müller"]);
+		assert_eq!(names("name!=A and NOT day=Di"), none);
+		assert_eq!(names("tag=vt and not tag=Erwachsene"), ["B"]);
+	}
+
+	#[test]
+	fn negation() {
+		let input = "A\n06151 111\nMo: 9:00 – 10:00\n\
+			Di: 9:00 – 10:00\n\n\
+			B\n06151 222\nMo: 9:00 – 10:00\n\n\
+			C\n06151 333\n\n\
+			D\n06151 444\nam 27.10.2026: 9:00 – 10:00\n";
+		let names = |query| names(input, query);
+		assert_eq!(names("not day=Di"), ["B", "C", "D"]);
+		assert_eq!(names("day!=Di"), ["A", "B"]);
+		assert_eq!(names("day=Di"), ["A"]);
+		assert_eq!(names("day=Mo and not day=Di"), ["B"]);
+	}
+
+	#[test]
+	fn status() {
+		let input = "A\n06151 111\n\nB\n06151 222\n\nC\n06151 333\n";
+		let log = "2026-10-19 09:05\ta\t\tAbsage\n\
+			2026-10-19 09:10\tb\t\tbesetzt\n\
+			2026-10-20 09:10\tb\t\tkeine Antwort\n";
+		let log = CallLog::from_str(log).unwrap();
+		let offices = hrdb::offices(input).unwrap().with_status(&log);
+		let names = |query| -> Vec<String> {
+			let query = Query::from_str(query).unwrap();
+			let found = offices.filter(&query, &today());
+			found.iter().map(|x| x.names.to_string()).collect()
+		};
+		assert_eq!(names("not status=Absage"), ["B", "C"]);
+		assert_eq!(names("status=\"keine Antwort\""), ["B"]);
+		assert!(names("status=besetzt").is_empty());
+		let error = Query::from_str("status=verwählt").unwrap_err();
+		assert_eq!(error.expected, Some("an outcome like besetzt"));
+	}
+
+	#[test]
+	fn errors() {
+		let error = |query| {
+			Query::from_str(query).unwrap_err().to_string()
+		};
+		let field = |rest| {
+			format!("expected {} at position {}", FIELD, rest)
+		};
+		assert_eq!(
+			error("day=Dienstag"),
+			"expected a day like Di at position 5, \
+				found \"Dienstag\"",
+		);
+		assert_eq!(
+			error("colour=blau"),
+			field("1, found \"colour=blau\""),
+		);
+		assert_eq!(
+			error("name<Müller"),
+			"expected = or != at position 5, found \"<Müller\"",
+		);
+		assert_eq!(
+			error("day=Di and"),
+			field("11, found end of query"),
+		);
+		assert_eq!(
+			error("(day=Di"),
+			"expected a closing parenthesis at position 8, \
+				found end of query",
+		);
+		let unexpected = "unexpected \"Mo\" at position 8";
+		assert_eq!(error("day=Di Mo"), unexpected);
+	}
+
+	#[test]
+	fn schedules() {
+		let input = "A\n06151 111\nDi: 9:00 – 10:00\n\
+			ab 01.01.2021:\nMo: 9:00 – 10:00\n\n\
+			B\n06151 222\nMo: 9:00 – 10:00\n\
+			ab 01.01.2030:\nDi: 9:00 – 10:00\n\n\
+			C\n06151 333\nbis 31.12.2020:\nDi: 9:00 – 10:00\n";
+		let names = |query| names(input, query);
+		assert_eq!(names("day=Di"), ["B"]);
+		assert_eq!(names("day=Mo"), ["A", "B"]);
+		assert_eq!(names("not day=Di"), ["A", "C"]);
+	}
+}
@@ -0,0 +1,180 @@
+//! Searching offices by what is remembered of them.
+//!
+//! Searches are forgiving: umlauts may be written as in `"Schoelling"`,
+//! `ß` as `"ss"`, case does not matter and small typos are tolerated.
+
+use crate::name;
+use crate::Office;
+
+/// Fold a text for searching according to DIN 5007-2, i.e. ignoring case
+/// and writing umlauts as `"ae"`, `"oe"` and `"ue"` and `ß` as `"ss"`.
+///
+/// Other diacritics are dropped as by `name::din_5007`, so that `"Michèle"`
+/// is found by `"Michele"`.
+pub fn fold(src: &str) -> String {
+	let mut expanded = String::with_capacity(src.len());
+	for c in src.chars().flat_map(char::to_lowercase) {
+		expanded.push(c);
+		if "äöü".contains(c) {
+			expanded.push('e');
+		}
+	}
+	name::din_5007(&expanded)
+}
+
+/// The folded words of a text.
+fn words(src: &str) -> Vec<String> {
+	fold(src)
+		.split(|c: char| !c.is_alphanumeric())
+		.filter(|x| !x.is_empty())
+		.map(String::from)
+		.collect()
+}
+
+/// The number of single character insertions, deletions, substitutions
+/// and transpositions of adjacent characters needed to turn one word into
+/// the other (the optimal string alignment distance).
+pub fn edit_distance(a: &str, b: &str) -> usize {
+	let a: Vec<char> = a.chars().collect();
+	let b: Vec<char> = b.chars().collect();
+	// d[i][j] is the distance between the first i chars of a and j of b
+	let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+	for (i, row) in d.iter_mut().enumerate() {
+		row[0] = i;
+	}
+	for (j, cell) in d[0].iter_mut().enumerate() {
+		*cell = j;
+	}
+	for i in 1..=a.len() {
+		for j in 1..=b.len() {
+			let cost = usize::from(a[i - 1] != b[j - 1]);
+			let mut best = (d[i - 1][j] + 1)
+				.min(d[i][j - 1] + 1)
+				.min(d[i - 1][j - 1] + cost);
+			let transposed = i > 1
+				&& j > 1 && a[i - 1] == b[j - 2]
+				&& a[i - 2] == b[j - 1];
+			if transposed {
+				best = best.min(d[i - 2][j - 2] + 1);
+			}
+			d[i][j] = best;
+		}
+	}
+	d[a.len()][b.len()]
+}
+
+/// The number of typos tolerated in a word of the query, more for longer
+/// words and none for very short ones.
+fn tolerance(word: &str) -> usize {
+	match word.chars().count() {
+		0..=3 => 0,
+		4..=7 => 1,
+		_ => 2,
+	}
+}
+
+/// How well a word of the query matches a word of the office, from 0 (not
+/// at all) to 3 (exactly).
+fn word_score(query: &str, word: &str) -> u32 {
+	if query == word {
+		3
+	} else if word.starts_with(query) {
+		2
+	} else if edit_distance(query, word) <= tolerance(query) {
+		1
+	} else {
+		0
+	}
+}
+
+/// The texts of an office to be searched, with their weight.
+///
+/// Names count most, since that is what is usually searched for.
+fn texts(office: &Office) -> Vec<(String, u32)> {
+	let mut texts = vec![(office.names.to_string(), 3)];
+	let details = office
+		.emails
+		.iter()
+		.map(ToString::to_string)
+		.chain(office.websites.iter().map(ToString::to_string))
+		.chain(office.addresses.iter().map(ToString::to_string))
+		.chain(office.comments.data.iter().map(|x| x.data.clone()))
+		.chain(office.notes.iter().map(|x| x.data.clone()))
+		.chain(office.tags.data.iter().cloned())
+		.chain(office.methods.data.iter().cloned())
+		.chain(office.age_groups.data.iter().cloned())
+		.chain(office.languages.data.iter().cloned())
+		.chain(office.unknown.iter().map(ToString::to_string));
+	texts.extend(details.map(|x| (x, 1)));
+	texts
+}
+
+/// How well the office matches the query, if all words of the query match
+/// somewhere.
+pub fn score(office: &Office, query: &str) -> Option<u32> {
+	let texts: Vec<(Vec<String>, u32)> = texts(office)
+		.into_iter()
+		.map(|(text, weight)| (words(&text), weight))
+		.collect();
+	let query = words(query);
+	if query.is_empty() {
+		return None;
+	}
+	let mut total = 0;
+	for query_word in &query {
+		let mut best = 0;
+		for (words, weight) in &texts {
+			for x in words {
+				let score = word_score(query_word, x) * weight;
+				best = best.max(score);
+			}
+		}
+		if best == 0 {
+			return None;
+		}
+		total += best;
+	}
+	Some(total)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn folding() {
+		assert_eq!(fold("Schölling"), fold("Schoelling"));
+		assert_eq!(fold("Allißat"), fold("Allissat"));
+		assert_eq!(fold("Michèle"), "michele");
+	}
+
+	#[test]
+	fn distance() {
+		assert_eq!(edit_distance("hegel", "hegel"), 0);
+		assert_eq!(edit_distance("hegel", "hgeel"), 1);
+		assert_eq!(edit_distance("hegel", "heggel"), 1);
+		assert_eq!(edit_distance("hegel", "nagel"), 2);
+		assert_eq!(edit_distance("", "abc"), 3);
+	}
+
+	#[test]
+	fn search() {
+		let input = "Daniela Schölling\n0123\n\n\
+			Ingrid Allißat, Iris Prawitz\n0456\n# Nur Kinder\n\n\
+			Klara Hegel\n0789\nNotiz: Empfohlen von Schölling\n";
+		let offices = crate::hrdb::offices(input).unwrap();
+		let names = |query| -> Vec<String> {
+			let found = offices.search(query);
+			found.data.iter().map(|x| x.names.to_string()).collect()
+		};
+		let both = ["Daniela Schölling", "Klara Hegel"];
+		assert_eq!(names("Schoelling"), both);
+		assert_eq!(names("schöling"), both);
+		assert_eq!(names("Daniela Scholling"), ["Daniela Schölling"]);
+		assert_eq!(names("Allissat"), ["Ingrid Allißat, Iris Prawitz"]);
+		assert_eq!(names("kinder"), ["Ingrid Allißat, Iris Prawitz"]);
+		assert_eq!(names("Hegl"), ["Klara Hegel"]);
+		assert!(names("Müller").is_empty());
+		assert!(names("").is_empty());
+	}
+}
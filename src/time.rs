@@ -1,7 +1,13 @@
 use std::cmp::{Ord, Ordering};
+use std::fmt;
+use std::str::FromStr;
 
-/// A day of the week (Mon – Fri, since noone can be reached on the weekend).
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+use crate::phone::Phone;
+use crate::Name;
+
+/// A day of the week (Mon – Fri, since noone can be reached on the weekend,
+/// apart from the end of hours crossing midnight on Friday).
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub enum Day {
 	Mon,
 	Tue,
@@ -51,17 +57,44 @@ impl TryFrom<time::Weekday> for Day {
 /// A time of the day in single-minute precision.
 /// (Though one would expect that much lower resolutions should be sufficient
 /// for the vast majority of cases. Phone times so rarely start at 13:07.)
+///
+/// Besides the usual times from 0:00 to 23:59, there is 24:00 to denote
+/// the very end of a day.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Clock {
 	hours: u8,
 	minutes: u8,
 }
 
+#[derive(Debug)]
+pub enum ClockError {
+	Hours,
+	Minutes,
+}
+
+impl Clock {
+	pub fn new(hours: u8, minutes: u8) -> Result<Clock, ClockError> {
+		if minutes > 59 {
+			Err(ClockError::Minutes)
+		} else if hours > 24 || (hours == 24 && minutes > 0) {
+			Err(ClockError::Hours)
+		} else {
+			Ok(Clock { hours, minutes })
+		}
+	}
+}
+
 impl Clock {
-	pub fn new(hours: u8, minutes: u8) -> Clock {
-		assert!(hours <= 23);
-		assert!(minutes <= 59);
-		Clock { hours, minutes }
+	/// Whether this is 24:00, the very end of a day.
+	pub fn is_end_of_day(&self) -> bool {
+		self.hours == 24
+	}
+}
+
+impl fmt::Display for Clock {
+	/// Writes the time as in the database, e.g. `"9:05"`.
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		write!(fmt, "{}:{:02}", self.hours, self.minutes)
 	}
 }
 
@@ -90,17 +123,19 @@ impl Ord for Clock {
 	}
 }
 
-/// A time of the week.
+/// A concrete point in time, i.e. a date and a time of that day.
+///
+/// The date may be on a weekend, when only the part of a window crossing
+/// midnight on Friday can contain it.
 #[derive(Debug)]
 pub struct Time {
-	day: Day,
+	date: time::Date,
 	clock: Clock,
 }
 
 #[derive(Debug)]
 pub enum NowError {
 	IndeterminateOffset(time::error::IndeterminateOffset),
-	Weekend(WeekendError),
 }
 
 impl From<time::error::IndeterminateOffset> for NowError {
@@ -109,39 +144,327 @@ impl From<time::error::IndeterminateOffset> for NowError {
 	}
 }
 
-impl From<WeekendError> for NowError {
-	fn from(e: WeekendError) -> Self {
-		NowError::Weekend(e)
+impl Time {
+	pub fn new(date: time::Date, clock: Clock) -> Time {
+		Time { date, clock }
 	}
-}
 
-impl Time {
 	pub fn now() -> Result<Time, NowError> {
 		let now = time::OffsetDateTime::now_local()?;
-		let day = Day::try_from(now.weekday())?;
 		let clock = Clock::from(now.time());
-		Ok(Time {day, clock})
+		Ok(Time::new(now.date(), clock))
+	}
+
+	pub fn date(&self) -> &time::Date {
+		&self.date
+	}
+}
+
+/// Whether a window from `begin` to `end`, starting on any date satisfying
+/// `starts_on`, contains the given time.
+///
+/// When `end` is before `begin`, the window crosses midnight and ends on
+/// the following day, which for a window starting on a Friday is the
+/// Saturday. Either way, `starts_on` is asked about the date the window
+/// started, e.g. whether the office was absent then.
+fn window_contains<F>(
+	begin: &Clock,
+	end: &Clock,
+	time: &Time,
+	starts_on: F,
+) -> bool
+where
+	F: Fn(&time::Date) -> bool,
+{
+	if begin < end {
+		let in_window = *begin <= time.clock && time.clock < *end;
+		in_window && starts_on(&time.date)
+	} else {
+		let evening = *begin <= time.clock && starts_on(&time.date);
+		let yesterday = time.date.previous_day();
+		let started = yesterday.is_some_and(|d| starts_on(&d));
+		let morning = time.clock < *end && started;
+		evening || morning
+	}
+}
+
+/// The parts of a window from `begin` to `end`, starting on any date
+/// satisfying `starts_on`, that fall on the given date, as in
+/// `window_contains`.
+fn window_on<F>(
+	begin: &Clock,
+	end: &Clock,
+	date: &time::Date,
+	starts_on: F,
+) -> Vec<(Clock, Clock)>
+where
+	F: Fn(&time::Date) -> bool,
+{
+	let midnight = Clock { hours: 0, minutes: 0 };
+	let end_of_day = Clock { hours: 24, minutes: 0 };
+	let mut parts = Vec::new();
+	if begin < end {
+		if starts_on(date) {
+			parts.push((begin.clone(), end.clone()));
+		}
+	} else {
+		let yesterday = date.previous_day();
+		if yesterday.is_some_and(|d| starts_on(&d)) {
+			parts.push((midnight, end.clone()));
+		}
+		if starts_on(date) {
+			parts.push((begin.clone(), end_of_day));
+		}
 	}
+	parts
 }
 
-/// The timing information of a single contiguous reachability by phone.
+/// Times of a day, as non-overlapping ranges from one `Clock` to a later
+/// one, e.g. the union of the hours of several offices.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Ranges {
+	/// The ranges sorted by their beginning, neither overlapping nor
+	/// touching each other.
+	data: Vec<(Clock, Clock)>,
+}
+
+impl Ranges {
+	/// Add the range from `begin` to `end`, merging it with those it
+	/// overlaps or touches.
+	pub fn insert(&mut self, begin: Clock, end: Clock) {
+		let (mut begin, mut end) = (begin, end);
+		let mut data = Vec::with_capacity(self.data.len() + 1);
+		for (b, e) in self.data.drain(..) {
+			if e < begin || end < b {
+				data.push((b, e));
+			} else {
+				begin = begin.min(b);
+				end = end.max(e);
+			}
+		}
+		data.push((begin, end));
+		data.sort();
+		self.data = data;
+	}
+
+	/// Add all ranges of the other ones.
+	pub fn extend(&mut self, other: Ranges) {
+		for (begin, end) in other.data {
+			self.insert(begin, end);
+		}
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.data.is_empty()
+	}
+}
+
+impl fmt::Display for Ranges {
+	/// Writes the ranges as in the database, e.g.
+	/// `"9:00 – 12:00, 14:00 – 15:00"`.
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		let ranges = self.data.iter().map(|(begin, end)| {
+			format!("{} – {}", begin, end)
+		});
+		crate::display_simple_list(ranges, fmt)
+	}
+}
+
+/// A restriction of a weekly `OfficeHour` to only some of the weeks.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Recurrence {
+	/// Only in even ISO calendar weeks.
+	EvenWeeks,
+	/// Only in odd ISO calendar weeks.
+	OddWeeks,
+	/// Only on the n-th occurrence of the weekday in a month, counting
+	/// from 1.
+	NthOfMonth(u8),
+	/// Only on the last occurrence of the weekday in a month.
+	LastOfMonth,
+	/// Only every n-th week, counting from the week containing the anchor.
+	EveryNWeeks(u8, time::Date),
+}
+
+impl Recurrence {
+	fn matches(&self, date: &time::Date) -> bool {
+		let week = date.iso_week();
+		let nth = (date.day() - 1) / 7 + 1;
+		match self {
+			Recurrence::EvenWeeks => week.is_multiple_of(2),
+			Recurrence::OddWeeks => !week.is_multiple_of(2),
+			Recurrence::NthOfMonth(n) => nth == *n,
+			Recurrence::LastOfMonth => {
+				let week_later = time::Duration::weeks(1);
+				match date.checked_add(week_later) {
+					Some(d) => d.month() != date.month(),
+					None => true,
+				}
+			}
+			Recurrence::EveryNWeeks(n, anchor) => {
+				let weeks = (monday(date) - monday(anchor)) / 7;
+				let n = i32::from(*n);
+				weeks.checked_rem_euclid(n) == Some(0)
+			}
+		}
+	}
+}
+
+/// The julian day of the monday in the week of a date.
+fn monday(date: &time::Date) -> i32 {
+	let offset = date.weekday().number_days_from_monday();
+	date.to_julian_day() - i32::from(offset)
+}
+
+/// The way in which an office can be reached during an `OfficeHour`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum Channel {
+	/// By phone, which is what office hours are usually about.
+	#[default]
+	Phone,
+	/// In person, without an appointment (offene Sprechstunde).
+	Consultation,
+	/// By video call.
+	Video,
+}
+
+/// All channels together with their German name.
+const CHANNELS: [(Channel, &str); 3] = [
+	(Channel::Phone, "Telefon"),
+	(Channel::Consultation, "Sprechstunde"),
+	(Channel::Video, "Video"),
+];
+
+#[derive(Debug)]
+pub enum ChannelErr {
+	Unknown,
+}
+
+impl FromStr for Channel {
+	type Err = ChannelErr;
+
+	/// Parses the German name of the channel, ignoring case.
+	fn from_str(src: &str) -> Result<Channel, Self::Err> {
+		CHANNELS
+			.iter()
+			.find(|(_, name)| name.eq_ignore_ascii_case(src))
+			.map(|(channel, _)| *channel)
+			.ok_or(ChannelErr::Unknown)
+	}
+}
+
+impl fmt::Display for Channel {
+	fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+		// This cannot panic! since every channel is in `CHANNELS`
+		let (_, name) =
+			CHANNELS.iter().find(|(c, _)| c == self).unwrap();
+		write!(fmt, "{}", name)
+	}
+}
+
+/// The timing information of a single contiguous reachability by phone
+/// (or by another `Channel`).
 #[derive(Debug, Clone)]
 pub struct OfficeHour {
 	day: Day,
 	begin: Clock,
 	end: Clock,
+	recurrence: Option<Recurrence>,
+	channel: Channel,
+	/// The phone answered during these hours, if not all of them are.
+	phone: Option<Phone>,
+	/// The person answering during these hours, if not the whole office.
+	person: Option<Name>,
 }
 
 impl OfficeHour {
-	pub fn new(day: Day, begin: Clock, end: Clock) -> OfficeHour {
-		OfficeHour { day, begin, end }
+	pub fn new(
+		day: Day,
+		begin: Clock,
+		end: Clock,
+		recurrence: Option<Recurrence>,
+		channel: Channel,
+		phone: Option<Phone>,
+		person: Option<Name>,
+	) -> OfficeHour {
+		OfficeHour {
+			day,
+			begin,
+			end,
+			recurrence,
+			channel,
+			phone,
+			person,
+		}
+	}
+
+	pub fn day(&self) -> &Day {
+		&self.day
+	}
+
+	pub fn begin(&self) -> &Clock {
+		&self.begin
+	}
+
+	pub fn end(&self) -> &Clock {
+		&self.end
+	}
+
+	pub fn channel(&self) -> &Channel {
+		&self.channel
+	}
+
+	pub fn phone(&self) -> Option<&Phone> {
+		self.phone.as_ref()
 	}
 
-	fn contains(&self, time: &Time) -> bool {
-		let same_day = self.day == time.day;
-		let after_begin = self.begin <= time.clock;
-		let before_end = self.end > time.clock;
-		same_day && after_begin && before_end
+	pub fn person(&self) -> Option<&Name> {
+		self.person.as_ref()
+	}
+
+	/// Whether the office hours take place starting on the given date,
+	/// i.e. it is the right day of the right week.
+	fn starts_on(&self, date: &time::Date) -> bool {
+		let day = Day::try_from(date.weekday());
+		let same_day = day.is_ok_and(|day| day == self.day);
+		let right_week = match &self.recurrence {
+			Some(recurrence) => recurrence.matches(date),
+			None => true,
+		};
+		same_day && right_week
+	}
+
+	/// Whether the office hours contain the given time for the given
+	/// channel, counting only those starting on a date for which `open`
+	/// holds.
+	fn contains<F>(&self, time: &Time, channel: &Channel, open: F) -> bool
+	where
+		F: Fn(&time::Date) -> bool,
+	{
+		if self.channel != *channel {
+			return false;
+		}
+		let starts_on = |d: &time::Date| self.starts_on(d) && open(d);
+		window_contains(&self.begin, &self.end, time, starts_on)
+	}
+
+	/// The parts of the office hours on the given date for the given
+	/// channel, counting only those starting on a date for which `open`
+	/// holds.
+	fn on<F>(
+		&self,
+		date: &time::Date,
+		channel: &Channel,
+		open: F,
+	) -> Vec<(Clock, Clock)>
+	where
+		F: Fn(&time::Date) -> bool,
+	{
+		if self.channel != *channel {
+			return Vec::new();
+		}
+		let starts_on = |d: &time::Date| self.starts_on(d) && open(d);
+		window_on(&self.begin, &self.end, date, starts_on)
 	}
 }
 
@@ -162,14 +485,375 @@ impl OfficeHours {
 		self.data.append(&mut new_times)
 	}
 
-	pub fn contain(&self, time: &Time) -> bool {
-		self.data.iter().any(|x| x.contains(time))
+	pub fn iter(&self) -> impl Iterator<Item = &OfficeHour> {
+		self.data.iter()
+	}
+
+	/// The office hours containing the given time for the given channel,
+	/// counting only those starting on a date for which `open` holds.
+	pub fn matching<'a, F>(
+		&'a self,
+		time: &'a Time,
+		channel: &'a Channel,
+		open: F,
+	) -> impl Iterator<Item = &'a OfficeHour>
+	where
+		F: Fn(&time::Date) -> bool + Copy + 'a,
+	{
+		self.data
+			.iter()
+			.filter(move |x| x.contains(time, channel, open))
+	}
+
+	/// The times on the given date covered by the office hours for the
+	/// given channel, counting only those starting on a date for which
+	/// `open` holds.
+	pub fn on<F>(
+		&self,
+		date: &time::Date,
+		channel: &Channel,
+		open: F,
+	) -> Ranges
+	where
+		F: Fn(&time::Date) -> bool + Copy,
+	{
+		let mut ranges = Ranges::default();
+		for x in &self.data {
+			for (begin, end) in x.on(date, channel, open) {
+				ranges.insert(begin, end);
+			}
+		}
+		ranges
+	}
+}
+
+/// A single additional reachability on a specific date, e.g. when an office
+/// asks to call back on Friday at two.
+///
+/// Since it only ever contains times on its date, it expires by itself once
+/// that date has passed.
+#[derive(Debug, Clone)]
+pub struct ExtraHour {
+	date: time::Date,
+	begin: Clock,
+	end: Clock,
+	channel: Channel,
+	/// The phone answered during these hours, if not all of them are.
+	phone: Option<Phone>,
+	/// The person answering during these hours, if not the whole office.
+	person: Option<Name>,
+}
+
+impl ExtraHour {
+	pub fn new(
+		date: time::Date,
+		begin: Clock,
+		end: Clock,
+		channel: Channel,
+		phone: Option<Phone>,
+		person: Option<Name>,
+	) -> ExtraHour {
+		ExtraHour { date, begin, end, channel, phone, person }
+	}
+
+	/// The day of the week of the date, unless it is on a weekend.
+	pub fn day(&self) -> Option<Day> {
+		Day::try_from(self.date.weekday()).ok()
+	}
+
+	pub fn begin(&self) -> &Clock {
+		&self.begin
+	}
+
+	pub fn end(&self) -> &Clock {
+		&self.end
+	}
+
+	pub fn channel(&self) -> &Channel {
+		&self.channel
+	}
+
+	pub fn phone(&self) -> Option<&Phone> {
+		self.phone.as_ref()
+	}
+
+	pub fn person(&self) -> Option<&Name> {
+		self.person.as_ref()
+	}
+
+	fn contains(&self, time: &Time, channel: &Channel) -> bool {
+		if self.channel != *channel {
+			return false;
+		}
+		let starts_on = |date: &time::Date| *date == self.date;
+		window_contains(&self.begin, &self.end, time, starts_on)
+	}
+
+	/// The parts of the extra hours on the given date for the given
+	/// channel.
+	fn on(
+		&self,
+		date: &time::Date,
+		channel: &Channel,
+	) -> Vec<(Clock, Clock)> {
+		if self.channel != *channel {
+			return Vec::new();
+		}
+		let starts_on = |date: &time::Date| *date == self.date;
+		window_on(&self.begin, &self.end, date, starts_on)
+	}
+}
+
+/// A set of `ExtraHour`s.
+#[derive(Debug, Clone)]
+pub struct ExtraHours {
+	data: Vec<ExtraHour>,
+}
+
+impl ExtraHours {
+	pub fn empty() -> ExtraHours {
+		let data = Vec::new();
+		ExtraHours { data }
+	}
+
+	pub fn append(&mut self, mut new_times: Vec<ExtraHour>) {
+		self.data.append(&mut new_times)
+	}
+
+	pub fn iter(&self) -> impl Iterator<Item = &ExtraHour> {
+		self.data.iter()
+	}
+
+	/// The extra hours containing the given time for the given channel.
+	pub fn matching<'a>(
+		&'a self,
+		time: &'a Time,
+		channel: &'a Channel,
+	) -> impl Iterator<Item = &'a ExtraHour> {
+		self.data.iter().filter(|x| x.contains(time, channel))
+	}
+
+	pub fn contain(&self, time: &Time, channel: &Channel) -> bool {
+		self.matching(time, channel).next().is_some()
+	}
+
+	/// The times on the given date covered by the extra hours for the
+	/// given channel.
+	pub fn on(&self, date: &time::Date, channel: &Channel) -> Ranges {
+		let mut ranges = Ranges::default();
+		for x in &self.data {
+			for (begin, end) in x.on(date, channel) {
+				ranges.insert(begin, end);
+			}
+		}
+		ranges
+	}
+}
+
+/// A set of `OfficeHours` valid only from and/or until some date (endpoints
+/// included), e.g. because the office announced new hours.
+#[derive(Debug, Clone)]
+pub struct Schedule {
+	from: Option<time::Date>,
+	until: Option<time::Date>,
+	hours: OfficeHours,
+}
+
+impl Schedule {
+	fn new(
+		from: Option<time::Date>,
+		until: Option<time::Date>,
+	) -> Schedule {
+		let hours = OfficeHours::empty();
+		Schedule { from, until, hours }
+	}
+
+	fn valid_at(&self, date: &time::Date) -> bool {
+		let after_from = self.from.is_none_or(|x| x <= *date);
+		let before_until = self.until.is_none_or(|x| *date <= x);
+		after_from && before_until
+	}
+}
+
+/// The successive `Schedule`s of an office.
+///
+/// There always is at least one schedule, the one valid without any
+/// restrictions. New office hours are added to the schedule started last.
+#[derive(Debug, Clone)]
+pub struct Schedules {
+	data: Vec<Schedule>,
+}
+
+impl Schedules {
+	pub fn new() -> Schedules {
+		let data = vec![Schedule::new(None, None)];
+		Schedules { data }
+	}
+
+	/// Start a new schedule valid in the given period.
+	pub fn start(
+		&mut self,
+		from: Option<time::Date>,
+		until: Option<time::Date>,
+	) {
+		self.data.push(Schedule::new(from, until))
+	}
+
+	pub fn append(&mut self, new_times: Vec<OfficeHour>) {
+		// This cannot panic! since there always is a schedule
+		self.data.last_mut().unwrap().hours.append(new_times)
+	}
+
+	/// The schedule valid at the given date.
+	///
+	/// When several schedules are valid, the one that came into effect last
+	/// wins, so that announced changes supersede the previous schedule.
+	pub fn valid_at(&self, date: &time::Date) -> Option<&Schedule> {
+		self.data
+			.iter()
+			.filter(|x| x.valid_at(date))
+			.max_by_key(|x| x.from)
+	}
+
+	/// The office hours of the schedule valid at the given date and of
+	/// those coming into effect later, but not of those that have expired
+	/// or been superseded by then.
+	pub fn hours_from<'a>(
+		&'a self,
+		date: &'a time::Date,
+	) -> impl Iterator<Item = &'a OfficeHour> {
+		let valid = self.valid_at(date);
+		let is_valid = move |x: &Schedule| {
+			valid.is_some_and(|valid| std::ptr::eq(valid, x))
+		};
+		let is_future = move |x: &Schedule| {
+			x.from.is_some_and(|from| from > *date)
+		};
+		self.data
+			.iter()
+			.filter(move |x| is_valid(x) || is_future(x))
+			.flat_map(|x| x.hours.iter())
+	}
+
+	/// Whether the given schedule is the one valid at the given date and
+	/// `open` holds for that date.
+	fn open_with<'a, F>(
+		&'a self,
+		schedule: &'a Schedule,
+		open: F,
+	) -> impl Fn(&time::Date) -> bool + Copy + 'a
+	where
+		F: Fn(&time::Date) -> bool + Copy + 'a,
+	{
+		move |date: &time::Date| {
+			let valid = self.valid_at(date);
+			let is_valid = |x: &Schedule| std::ptr::eq(x, schedule);
+			valid.is_some_and(is_valid) && open(date)
+		}
+	}
+
+	/// The office hours containing the given time for the given channel.
+	///
+	/// Office hours count only if they start on a date at which their
+	/// schedule is valid and for which `open` holds, e.g. the office is
+	/// not absent. For a window crossing midnight, this is the date before
+	/// the given time.
+	pub fn matching<'a, F>(
+		&'a self,
+		time: &'a Time,
+		channel: &'a Channel,
+		open: F,
+	) -> impl Iterator<Item = &'a OfficeHour>
+	where
+		F: Fn(&time::Date) -> bool + Copy + 'a,
+	{
+		self.data.iter().flat_map(move |x| {
+			x.hours.matching(time, channel, self.open_with(x, open))
+		})
+	}
+
+	pub fn contain<F>(
+		&self,
+		time: &Time,
+		channel: &Channel,
+		open: F,
+	) -> bool
+	where
+		F: Fn(&time::Date) -> bool + Copy,
+	{
+		self.matching(time, channel, open).next().is_some()
+	}
+
+	/// The times on the given date covered by the office hours for the
+	/// given channel, counting only those starting on a date as in
+	/// `matching`.
+	pub fn on<F>(
+		&self,
+		date: &time::Date,
+		channel: &Channel,
+		open: F,
+	) -> Ranges
+	where
+		F: Fn(&time::Date) -> bool + Copy,
+	{
+		let mut ranges = Ranges::default();
+		for x in &self.data {
+			let open = self.open_with(x, open);
+			ranges.extend(x.hours.on(date, channel, open));
+		}
+		ranges
+	}
+}
+
+impl Default for Schedules {
+	fn default() -> Schedules {
+		Schedules::new()
+	}
+}
+
+/// A period of whole days (endpoints included) during which an office
+/// cannot be reached at all, e.g. the summer vacation.
+#[derive(Debug, Clone)]
+pub struct Absence {
+	begin: time::Date,
+	end: time::Date,
+}
+
+impl Absence {
+	pub fn new(begin: time::Date, end: time::Date) -> Absence {
+		Absence { begin, end }
+	}
+
+	fn contains(&self, date: &time::Date) -> bool {
+		self.begin <= *date && *date <= self.end
+	}
+}
+
+/// A set of `Absence`s.
+#[derive(Debug, Clone)]
+pub struct Absences {
+	data: Vec<Absence>,
+}
+
+impl Absences {
+	pub fn empty() -> Absences {
+		let data = Vec::new();
+		Absences { data }
+	}
+
+	pub fn push(&mut self, absence: Absence) {
+		self.data.push(absence)
+	}
+
+	pub fn contain(&self, date: &time::Date) -> bool {
+		self.data.iter().any(|x| x.contains(date))
 	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use time::Month;
 
 	#[test]
 	fn after_monday() {
@@ -185,7 +869,7 @@ mod tests {
 	#[test]
 	fn clock_works() {
 		assert_eq!(
-			Clock::new(11, 23),
+			Clock::new(11, 23).unwrap(),
 			Clock {
 				hours: 11,
 				minutes: 23
@@ -194,14 +878,264 @@ mod tests {
 	}
 
 	#[test]
-	#[should_panic]
 	fn clock_fail_hours() {
-		Clock::new(24, 13);
+		assert!(Clock::new(24, 13).is_err());
+		assert!(Clock::new(25, 0).is_err());
 	}
 
 	#[test]
-	#[should_panic]
 	fn clock_fail_minutes() {
-		Clock::new(20, 60);
+		assert!(Clock::new(20, 60).is_err());
+	}
+
+	#[test]
+	fn clock_end_of_day() {
+		assert!(clock(23, 59) < clock(24, 0));
+	}
+
+	#[test]
+	fn office_hour_overnight() {
+		let hour = OfficeHour::new(
+			Day::Thu,
+			clock(22, 0),
+			clock(2, 0),
+			None,
+			Channel::Phone,
+			None,
+			None,
+		);
+		let at = |day, hours| {
+			let date = ymd(2026, Month::October, day);
+			Time::new(date, clock(hours, 30))
+		};
+		// The 22nd of October 2026 is a Thursday
+		assert!(hour.contains(&at(22, 23), &PHONE, always));
+		assert!(hour.contains(&at(23, 1), &PHONE, always));
+		assert!(!hour.contains(&at(22, 1), &PHONE, always));
+		assert!(!hour.contains(&at(23, 23), &PHONE, always));
+		assert!(!hour.contains(&at(23, 2), &PHONE, always));
+	}
+
+	#[test]
+	fn office_hour_until_midnight() {
+		let hour = OfficeHour::new(
+			Day::Thu,
+			clock(20, 0),
+			clock(24, 0),
+			None,
+			Channel::Phone,
+			None,
+			None,
+		);
+		let date = ymd(2026, Month::October, 22);
+		let late = Time::new(date, clock(23, 59));
+		assert!(hour.contains(&late, &PHONE, always));
+		let next = ymd(2026, Month::October, 23);
+		let early = Time::new(next, clock(0, 0));
+		assert!(!hour.contains(&early, &PHONE, always));
+	}
+
+	#[test]
+	fn office_hour_overnight_into_weekend() {
+		let hour = OfficeHour::new(
+			Day::Fri,
+			clock(22, 0),
+			clock(2, 0),
+			None,
+			Channel::Phone,
+			None,
+			None,
+		);
+		// The 23rd of October 2026 is a Friday
+		let friday = ymd(2026, Month::October, 23);
+		let late = Time::new(friday, clock(23, 0));
+		assert!(hour.contains(&late, &PHONE, always));
+		let saturday = ymd(2026, Month::October, 24);
+		let early = Time::new(saturday, clock(1, 0));
+		assert!(hour.contains(&early, &PHONE, always));
+		let late = Time::new(saturday, clock(23, 0));
+		assert!(!hour.contains(&late, &PHONE, always));
+	}
+
+	#[test]
+	fn office_hour_overnight_open() {
+		let mut schedules = Schedules::new();
+		schedules.append(vec![OfficeHour::new(
+			Day::Mon,
+			clock(22, 0),
+			clock(2, 0),
+			None,
+			PHONE,
+			None,
+			None,
+		)]);
+		// The 26th of October 2026 is a Monday
+		let monday = ymd(2026, Month::October, 26);
+		let tuesday = ymd(2026, Month::October, 27);
+		let early = Time::new(tuesday, clock(1, 0));
+		let not_on = |day| move |date: &time::Date| *date != day;
+		assert!(schedules.contain(&early, &PHONE, not_on(tuesday)));
+		assert!(!schedules.contain(&early, &PHONE, not_on(monday)));
+		let hours = schedules.on(&tuesday, &PHONE, not_on(monday));
+		assert!(hours.is_empty());
+	}
+
+	const PHONE: Channel = Channel::Phone;
+
+	fn always(_: &time::Date) -> bool {
+		true
+	}
+
+	fn clock(hours: u8, minutes: u8) -> Clock {
+		Clock::new(hours, minutes).unwrap()
+	}
+
+	fn ymd(year: i32, month: Month, day: u8) -> time::Date {
+		time::Date::from_calendar_date(year, month, day).unwrap()
+	}
+
+	#[test]
+	fn ranges_union() {
+		let mut ranges = Ranges::default();
+		ranges.insert(clock(14, 0), clock(15, 0));
+		ranges.insert(clock(9, 0), clock(11, 0));
+		ranges.insert(clock(10, 0), clock(12, 0));
+		assert_eq!(ranges.to_string(), "9:00 – 12:00, 14:00 – 15:00");
+		ranges.insert(clock(12, 0), clock(14, 0));
+		assert_eq!(ranges.to_string(), "9:00 – 15:00");
+	}
+
+	#[test]
+	fn office_hour_overnight_on() {
+		let hours = OfficeHours {
+			data: vec![OfficeHour::new(
+				Day::Thu,
+				clock(22, 0),
+				clock(2, 0),
+				None,
+				PHONE,
+				None,
+				None,
+			)],
+		};
+		// The 22nd of October 2026 is a Thursday
+		let on = |day| {
+			let date = ymd(2026, Month::October, day);
+			hours.on(&date, &PHONE, always)
+		};
+		let thursday = on(22);
+		assert_eq!(thursday.to_string(), "22:00 – 24:00");
+		let friday = on(23);
+		assert_eq!(friday.to_string(), "0:00 – 2:00");
+	}
+
+	#[test]
+	fn channel_names() {
+		let channel = Channel::from_str("sprechstunde").unwrap();
+		assert_eq!(channel, Channel::Consultation);
+		assert_eq!(channel.to_string(), "Sprechstunde");
+		assert!(Channel::from_str("Fax").is_err());
+	}
+
+	#[test]
+	fn office_hour_channel() {
+		let hour = OfficeHour::new(
+			Day::Thu,
+			clock(9, 0),
+			clock(10, 0),
+			None,
+			Channel::Video,
+			None,
+			None,
+		);
+		let date = ymd(2026, Month::October, 22);
+		let time = Time::new(date, clock(9, 30));
+		assert!(hour.contains(&time, &Channel::Video, always));
+		assert!(!hour.contains(&time, &PHONE, always));
+	}
+
+	#[test]
+	fn absence_includes_endpoints() {
+		let mut absences = Absences::empty();
+		absences.push(Absence::new(
+			ymd(2026, Month::August, 1),
+			ymd(2026, Month::August, 15),
+		));
+		assert!(absences.contain(&ymd(2026, Month::August, 1)));
+		assert!(absences.contain(&ymd(2026, Month::August, 15)));
+		assert!(!absences.contain(&ymd(2026, Month::August, 16)));
+	}
+
+	#[test]
+	fn schedule_changes() {
+		let hour = |day| {
+			vec![OfficeHour::new(
+				day,
+				clock(9, 0),
+				clock(10, 0),
+				None,
+				PHONE,
+				None,
+				None,
+			)]
+		};
+		let mut schedules = Schedules::new();
+		schedules.append(hour(Day::Mon));
+		schedules.start(Some(ymd(2026, Month::November, 1)), None);
+		schedules.append(hour(Day::Tue));
+		let at = |day| {
+			let date = ymd(2026, Month::November, day);
+			Time::new(date, clock(9, 30))
+		};
+		// The 2nd and 3rd of November 2026 are a Monday and Tuesday
+		assert!(!schedules.contain(&at(2), &PHONE, always));
+		assert!(schedules.contain(&at(3), &PHONE, always));
+		let before = ymd(2026, Month::October, 26);
+		let before = Time::new(before, clock(9, 30));
+		assert!(schedules.contain(&before, &PHONE, always));
+	}
+
+	#[test]
+	fn recurrence_weeks() {
+		// 14.10.2026 is in week 42, 21.10.2026 in week 43
+		let even = ymd(2026, Month::October, 14);
+		let odd = ymd(2026, Month::October, 21);
+		assert!(Recurrence::EvenWeeks.matches(&even));
+		assert!(!Recurrence::EvenWeeks.matches(&odd));
+		assert!(Recurrence::OddWeeks.matches(&odd));
+		let every_third = Recurrence::EveryNWeeks(3, odd);
+		assert!(every_third.matches(&ymd(2026, Month::November, 13)));
+		assert!(!every_third.matches(&ymd(2026, Month::November, 4)));
+		assert!(every_third.matches(&ymd(2026, Month::September, 30)));
+		assert!(!Recurrence::EveryNWeeks(0, odd).matches(&odd));
+	}
+
+	#[test]
+	fn recurrence_month() {
+		let first = ymd(2026, Month::October, 7);
+		let last = ymd(2026, Month::October, 28);
+		assert!(Recurrence::NthOfMonth(1).matches(&first));
+		assert!(!Recurrence::NthOfMonth(1).matches(&last));
+		assert!(Recurrence::NthOfMonth(4).matches(&last));
+		assert!(Recurrence::LastOfMonth.matches(&last));
+		assert!(!Recurrence::LastOfMonth.matches(&first));
+	}
+
+	#[test]
+	fn office_hour_recurrence() {
+		let hour = OfficeHour::new(
+			Day::Wed,
+			clock(9, 0),
+			clock(10, 0),
+			Some(Recurrence::EvenWeeks),
+			Channel::Phone,
+			None,
+			None,
+		);
+		let even = ymd(2026, Month::October, 14);
+		let odd = ymd(2026, Month::October, 21);
+		let at = |date| Time::new(date, clock(9, 30));
+		assert!(hour.contains(&at(even), &PHONE, always));
+		assert!(!hour.contains(&at(odd), &PHONE, always));
 	}
 }